@@ -1,25 +1,23 @@
 #![allow(dead_code)]
 
-//! An example CLI that uses `FileOrStdin` for a serde_json deserializable value
+//! An example CLI that uses `FileOrStdin<Json<T>>` for a serde_json deserializable value
 //! from a file or stdin
 //!
 //! Example usage:
 //! ```sh
 //! # via stdin
-//! $ echo '{ "name": "Trinity", "age": 30 }' | cargo run --example parse_with_serde
+//! $ echo '{ "name": "Trinity", "age": 30 }' | cargo run --features json --example parse_with_serde
 //!
 //! # via file read
 //! $ cat contents.json
 //! '{ "name": "Trinity", "age": 30 }'
-//! $ cargo run --example parse_with_serde -- ./contents.json
+//! $ cargo run --features json --example parse_with_serde -- ./contents.json
 //!
 //! # Using tokio AsyncRead
-//! $ cargo run --features tokio --example parse_with_serde -- ./contents.json
+//! $ cargo run --features tokio,json --example parse_with_serde -- ./contents.json
 //! ```
-use std::str::FromStr;
-
 use clap::Parser;
-use clap_stdin::FileOrStdin;
+use clap_stdin::{FileOrStdin, Json};
 use serde::Deserialize;
 
 #[derive(Clone, Debug, Deserialize)]
@@ -28,25 +26,17 @@ struct User {
     age: u8,
 }
 
-impl FromStr for User {
-    type Err = serde_json::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        serde_json::from_str(s)
-    }
-}
-
 #[derive(Debug, Parser)]
 struct Args {
     /// Parsed user from json, provided via a filepath (or leave blank to read from stdin)
     #[arg(default_value = "-")]
-    user: FileOrStdin<User>,
+    user: FileOrStdin<Json<User>>,
 }
 
 #[cfg(not(feature = "tokio"))]
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    eprintln!("{:?}", args.user.contents()?);
+    eprintln!("{:?}", args.user.deserialize()?.into_inner());
     Ok(())
 }
 
@@ -54,6 +44,6 @@ fn main() -> anyhow::Result<()> {
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    eprintln!("{:?}", args.user.contents_async().await?);
+    eprintln!("{:?}", args.user.deserialize_async().await?.into_inner());
     Ok(())
 }
@@ -0,0 +1,311 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::{Dest, OutputWriter, WriteMode};
+
+/// Writer returned by [`FileOrStdout::into_atomic_writer`](crate::FileOrStdout::into_atomic_writer)
+///
+/// Output for a file destination is written to a temporary file in the same directory, which
+/// only replaces the destination file when [`AtomicWriter::commit`] is called. If the writer is
+/// dropped without committing, the temporary file is removed and the destination is untouched.
+/// In append mode, the temporary file starts with a copy of the existing destination contents.
+///
+/// When the destination is `stdout` or an inherited file descriptor, writes are passed through directly.
+pub struct AtomicWriter {
+    writer: OutputWriter,
+    target: Option<Target>,
+}
+
+impl AtomicWriter {
+    pub(crate) fn new(
+        dest: Dest,
+        mode: WriteMode,
+        #[cfg(feature = "compression")] codec: Option<super::Compression>,
+    ) -> io::Result<Self> {
+        let (writer, target): (Box<dyn Write>, _) = match dest {
+            Dest::Stdout => (super::StdinContext::current().stdout(), None),
+            Dest::Fd { fd, .. } => (Box::new(super::open_fd(fd)?), None),
+            Dest::Arg(path) => {
+                let target = Target::new(path, mode)?;
+                (Box::new(target.tmp.as_file().try_clone()?), Some(target))
+            }
+        };
+        let writer = OutputWriter::new(
+            writer,
+            #[cfg(feature = "compression")]
+            codec,
+        )?;
+        Ok(Self { writer, target })
+    }
+
+    /// Sync the file contents to disk before it replaces the destination (default: `false`)
+    pub fn with_fsync(mut self, fsync: bool) -> Self {
+        if let Some(target) = self.target.as_mut() {
+            target.fsync = fsync;
+        }
+        self
+    }
+
+    /// The path of the temporary file being written to, if the destination is a file
+    pub fn temp_path(&self) -> Option<&Path> {
+        self.target.as_ref().map(|target| target.tmp.path())
+    }
+
+    /// Finish writing and move the temporary file into place of the destination file
+    pub fn commit(self) -> io::Result<()> {
+        self.writer.finish()?;
+        match self.target {
+            Some(target) => target.persist(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for AtomicWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl std::fmt::Debug for AtomicWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.target {
+            Some(target) => target.fmt_debug("AtomicWriter", f),
+            None => write!(f, "AtomicWriter(stdout)"),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+/// Writer returned by [`FileOrStdout::into_async_atomic_writer`](crate::FileOrStdout::into_async_atomic_writer)
+///
+/// The async equivalent of [`AtomicWriter`]: the destination file is only replaced when
+/// [`AsyncAtomicWriter::commit`] is called, and the temporary file is removed if dropped
+/// without committing.
+pub struct AsyncAtomicWriter {
+    writer: std::pin::Pin<Box<dyn tokio::io::AsyncWrite>>,
+    target: Option<Target>,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncAtomicWriter {
+    pub(crate) fn new(
+        dest: Dest,
+        mode: WriteMode,
+        #[cfg(feature = "compression")] codec: Option<super::Compression>,
+    ) -> io::Result<Self> {
+        let (writer, target): (std::pin::Pin<Box<dyn tokio::io::AsyncWrite>>, _) = match dest {
+            Dest::Stdout => (super::StdinContext::current().async_stdout(), None),
+            Dest::Fd { fd, .. } => {
+                let file = tokio::fs::File::from_std(super::open_fd(fd)?);
+                (Box::pin(file), None)
+            }
+            Dest::Arg(path) => {
+                let target = Target::new(path, mode)?;
+                let file = tokio::fs::File::from_std(target.tmp.as_file().try_clone()?);
+                (Box::pin(file), Some(target))
+            }
+        };
+        #[cfg(feature = "compression")]
+        let writer = super::compression::encode_async(writer, codec);
+        Ok(Self { writer, target })
+    }
+
+    /// Sync the file contents to disk before it replaces the destination (default: `false`)
+    pub fn with_fsync(mut self, fsync: bool) -> Self {
+        if let Some(target) = self.target.as_mut() {
+            target.fsync = fsync;
+        }
+        self
+    }
+
+    /// The path of the temporary file being written to, if the destination is a file
+    pub fn temp_path(&self) -> Option<&Path> {
+        self.target.as_ref().map(|target| target.tmp.path())
+    }
+
+    /// Flush and finish writing, then move the temporary file into place of the destination file
+    pub async fn commit(mut self) -> io::Result<()> {
+        tokio::io::AsyncWriteExt::shutdown(&mut self.writer).await?;
+        match self.target {
+            Some(target) => target.persist(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncWrite for AsyncAtomicWriter {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        self.writer.as_mut().poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.writer.as_mut().poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.writer.as_mut().poll_shutdown(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl std::fmt::Debug for AsyncAtomicWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.target {
+            Some(target) => target.fmt_debug("AsyncAtomicWriter", f),
+            None => write!(f, "AsyncAtomicWriter(stdout)"),
+        }
+    }
+}
+
+/// Temporary file that will replace the destination file when persisted
+struct Target {
+    tmp: tempfile::NamedTempFile,
+    path: PathBuf,
+    fsync: bool,
+    no_clobber: bool,
+}
+
+impl Target {
+    fn new(path: String, mode: WriteMode) -> io::Result<Self> {
+        let no_clobber = mode == WriteMode::CreateNew;
+        if no_clobber && Path::new(&path).exists() {
+            return Err(super::overwrite_refused(&path));
+        }
+        let path = PathBuf::from(path);
+        let mut tmp = temp_file_for(&path)?;
+        if mode == WriteMode::Append {
+            match std::fs::File::open(&path) {
+                Ok(mut existing) => {
+                    io::copy(&mut existing, &mut tmp)?;
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Self {
+            tmp,
+            path,
+            fsync: false,
+            no_clobber,
+        })
+    }
+
+    fn persist(self) -> io::Result<()> {
+        let Self {
+            tmp,
+            path,
+            fsync,
+            no_clobber,
+        } = self;
+        if fsync {
+            tmp.as_file().sync_all()?;
+        }
+        if no_clobber {
+            tmp.persist_noclobber(&path)
+                .map_err(|e| WriteMode::CreateNew.open_error(e.error, &path.to_string_lossy()))?;
+        } else {
+            tmp.persist(&path).map_err(|e| e.error)?;
+        }
+        #[cfg(unix)]
+        if fsync {
+            std::fs::File::open(parent_dir(&path))?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    fn fmt_debug(&self, name: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(name)
+            .field("path", &self.path)
+            .field("temp_path", &self.tmp.path())
+            .finish()
+    }
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Create a temp file next to `path`, with the permissions of the existing file (if any)
+fn temp_file_for(path: &Path) -> io::Result<tempfile::NamedTempFile> {
+    let existing = std::fs::metadata(path).ok().map(|m| m.permissions());
+    let mut builder = tempfile::Builder::new();
+    let prefix = format!(
+        ".{}.",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    );
+    builder.prefix(&prefix).suffix(".tmp");
+    #[cfg(unix)]
+    if existing.is_none() {
+        use std::os::unix::fs::PermissionsExt;
+        // Match the permissions of a newly created file (subject to the umask)
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let tmp = builder.tempfile_in(parent_dir(path))?;
+    if let Some(permissions) = existing {
+        tmp.as_file().set_permissions(permissions)?;
+    }
+    Ok(tmp)
+}
+
+#[test]
+fn test_atomic_writer() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("output.txt");
+    std::fs::write(&path, "ORIGINAL").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+    }
+
+    let new_writer = || {
+        AtomicWriter::new(
+            Dest::Arg(path.to_str().unwrap().to_owned()),
+            WriteMode::Truncate,
+            #[cfg(feature = "compression")]
+            None,
+        )
+        .unwrap()
+    };
+
+    // Dropped without committing, the original is untouched
+    let mut writer = new_writer();
+    write!(writer, "DISCARDED").unwrap();
+    let temp_path = writer.temp_path().unwrap().to_owned();
+    assert!(temp_path.exists());
+    drop(writer);
+    assert!(!temp_path.exists());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "ORIGINAL");
+
+    let mut writer = new_writer().with_fsync(true);
+    write!(writer, "COMMITTED").unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "ORIGINAL");
+    writer.commit().unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "COMMITTED");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
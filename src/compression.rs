@@ -0,0 +1,372 @@
+use std::io::{self, Read, Write};
+
+/// Longest magic number of the supported codecs (xz)
+const MAGIC_LEN: usize = 6;
+
+#[cfg(feature = "tokio")]
+type AsyncBufReader = tokio::io::BufReader<std::pin::Pin<Box<dyn tokio::io::AsyncRead>>>;
+
+/// Compression codecs that can be transparently handled, each enabled by the cargo feature
+/// of the same name (`gzip`, `zstd`, `xz`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Compression {
+    #[cfg(feature = "gzip")]
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    #[cfg(feature = "xz")]
+    Xz,
+}
+
+impl Compression {
+    /// Detect the codec from the leading magic bytes of a stream
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        #[cfg(feature = "gzip")]
+        if header.starts_with(&[0x1f, 0x8b]) {
+            return Some(Self::Gzip);
+        }
+        #[cfg(feature = "zstd")]
+        if header.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            return Some(Self::Zstd);
+        }
+        #[cfg(feature = "xz")]
+        if header.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            return Some(Self::Xz);
+        }
+        let _ = header;
+        None
+    }
+
+    /// Detect the codec from a file extension (`.gz`, `.zst`, `.xz`)
+    pub fn from_extension(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        match extension {
+            #[cfg(feature = "gzip")]
+            "gz" => Some(Self::Gzip),
+            #[cfg(feature = "zstd")]
+            "zst" => Some(Self::Zstd),
+            #[cfg(feature = "xz")]
+            "xz" => Some(Self::Xz),
+            _ => None,
+        }
+    }
+
+    /// Wrap the reader with a decoder for this codec
+    fn decoder(self, reader: Box<dyn Read>) -> io::Result<Box<dyn Read>> {
+        #[cfg(not(any(feature = "gzip", feature = "zstd", feature = "xz")))]
+        let _ = reader;
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Ok(Box::new(zstd::stream::read::Decoder::new(reader)?)),
+            #[cfg(feature = "xz")]
+            Self::Xz => Ok(Box::new(liblzma::read::XzDecoder::new_multi_decoder(
+                reader,
+            ))),
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Wrap the reader with an `async-compression` decoder for this codec
+    fn async_decoder(self, reader: AsyncBufReader) -> std::pin::Pin<Box<dyn tokio::io::AsyncRead>> {
+        #[cfg(not(any(feature = "gzip", feature = "zstd", feature = "xz")))]
+        let _ = reader;
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                let mut decoder = async_compression::tokio::bufread::GzipDecoder::new(reader);
+                decoder.multiple_members(true);
+                Box::pin(decoder)
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                let mut decoder = async_compression::tokio::bufread::ZstdDecoder::new(reader);
+                decoder.multiple_members(true);
+                Box::pin(decoder)
+            }
+            #[cfg(feature = "xz")]
+            Self::Xz => {
+                let mut decoder = async_compression::tokio::bufread::XzDecoder::new(reader);
+                decoder.multiple_members(true);
+                Box::pin(decoder)
+            }
+        }
+    }
+}
+
+/// How an input source should be decompressed, or an output destination compressed
+///
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{Compression, CompressionMode, FileOrStdin};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: FileOrStdin,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let input = args
+///     .input
+///     .with_compression(CompressionMode::Forced(Compression::Gzip))
+///     .contents()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompressionMode {
+    /// Detect the codec from the magic bytes of the input contents,
+    /// or from the file extension of the output destination
+    #[default]
+    Auto,
+    /// Read or write the contents as-is
+    Disabled,
+    /// Always (de)compress with the given codec
+    Forced(Compression),
+}
+
+/// Read up to `MAGIC_LEN` bytes from the reader without losing them for the caller
+fn peek<R: Read>(mut reader: R) -> io::Result<(Vec<u8>, R)> {
+    let mut header = vec![0; MAGIC_LEN];
+    let mut filled = 0;
+    while filled < MAGIC_LEN {
+        match reader.read(&mut header[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    header.truncate(filled);
+    Ok((header, reader))
+}
+
+/// Wrap the reader with a decoder for the codec chosen by `mode`
+pub(crate) fn decode<R: Read + 'static>(
+    reader: R,
+    mode: CompressionMode,
+) -> io::Result<Box<dyn Read>> {
+    let (codec, reader): (_, Box<dyn Read>) = match mode {
+        CompressionMode::Disabled => return Ok(Box::new(reader)),
+        CompressionMode::Forced(codec) => (codec, Box::new(reader)),
+        CompressionMode::Auto => {
+            let (header, reader) = peek(reader)?;
+            let codec = Compression::from_magic(&header);
+            let reader = Box::new(io::Cursor::new(header).chain(reader));
+            match codec {
+                Some(codec) => (codec, reader),
+                None => return Ok(reader),
+            }
+        }
+    };
+    codec.decoder(reader)
+}
+
+#[cfg(feature = "tokio")]
+/// Async version of [`decode`], wrapping the reader with an `async-compression` decoder
+pub(crate) async fn decode_async<R: tokio::io::AsyncRead + Unpin + 'static>(
+    mut reader: R,
+    mode: CompressionMode,
+) -> io::Result<std::pin::Pin<Box<dyn tokio::io::AsyncRead>>> {
+    use tokio::io::{AsyncReadExt, BufReader};
+
+    let (codec, reader): (_, std::pin::Pin<Box<dyn tokio::io::AsyncRead>>) = match mode {
+        CompressionMode::Disabled => return Ok(Box::pin(reader)),
+        CompressionMode::Forced(codec) => (codec, Box::pin(reader)),
+        CompressionMode::Auto => {
+            let mut header = vec![0; MAGIC_LEN];
+            let mut filled = 0;
+            while filled < MAGIC_LEN {
+                match reader.read(&mut header[filled..]).await? {
+                    0 => break,
+                    n => filled += n,
+                }
+            }
+            header.truncate(filled);
+            let codec = Compression::from_magic(&header);
+            let reader = Box::pin(AsyncReadExt::chain(io::Cursor::new(header), reader));
+            match codec {
+                Some(codec) => (codec, reader),
+                None => return Ok(reader),
+            }
+        }
+    };
+    Ok(codec.async_decoder(BufReader::new(reader)))
+}
+
+/// Writer that compresses with the chosen codec (or passes through when there is none),
+/// finalizing the compressed frame on [`Encoder::finish`] or when dropped
+pub(crate) struct Encoder<W: Write> {
+    inner: Option<EncoderInner<W>>,
+}
+
+enum EncoderInner<W: Write> {
+    Plain(W),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<W>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+    #[cfg(feature = "xz")]
+    Xz(liblzma::write::XzEncoder<W>),
+}
+
+impl<W: Write> Encoder<W> {
+    pub(crate) fn new(writer: W, codec: Option<Compression>) -> io::Result<Self> {
+        let inner = match codec {
+            None => EncoderInner::Plain(writer),
+            #[cfg(feature = "gzip")]
+            Some(Compression::Gzip) => EncoderInner::Gzip(flate2::write::GzEncoder::new(
+                writer,
+                flate2::Compression::default(),
+            )),
+            #[cfg(feature = "zstd")]
+            Some(Compression::Zstd) => {
+                EncoderInner::Zstd(zstd::stream::write::Encoder::new(writer, 0)?)
+            }
+            #[cfg(feature = "xz")]
+            Some(Compression::Xz) => EncoderInner::Xz(liblzma::write::XzEncoder::new(writer, 6)),
+        };
+        Ok(Self { inner: Some(inner) })
+    }
+
+    /// Finalize the compressed frame, returning the underlying writer
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        self.inner
+            .take()
+            .expect("encoder is only taken when finished or dropped")
+            .finish()
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self
+            .inner
+            .as_mut()
+            .expect("encoder is only taken when finished or dropped")
+        {
+            EncoderInner::Plain(writer) => writer,
+            #[cfg(feature = "gzip")]
+            EncoderInner::Gzip(encoder) => encoder,
+            #[cfg(feature = "zstd")]
+            EncoderInner::Zstd(encoder) => encoder,
+            #[cfg(feature = "xz")]
+            EncoderInner::Xz(encoder) => encoder,
+        }
+    }
+}
+
+impl<W: Write> EncoderInner<W> {
+    #[cfg_attr(
+        not(any(feature = "gzip", feature = "zstd", feature = "xz")),
+        allow(clippy::infallible_destructuring_match)
+    )]
+    fn finish(self) -> io::Result<W> {
+        let mut writer = match self {
+            EncoderInner::Plain(writer) => writer,
+            #[cfg(feature = "gzip")]
+            EncoderInner::Gzip(encoder) => encoder.finish()?,
+            #[cfg(feature = "zstd")]
+            EncoderInner::Zstd(encoder) => encoder.finish()?,
+            #[cfg(feature = "xz")]
+            EncoderInner::Xz(encoder) => encoder.finish()?,
+        };
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer().flush()
+    }
+}
+
+impl<W: Write> Drop for Encoder<W> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            let _ = inner.finish();
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+/// Async version of [`Encoder`], the compressed frame is finalized on `shutdown`
+pub(crate) fn encode_async(
+    writer: std::pin::Pin<Box<dyn tokio::io::AsyncWrite>>,
+    codec: Option<Compression>,
+) -> std::pin::Pin<Box<dyn tokio::io::AsyncWrite>> {
+    match codec {
+        None => writer,
+        #[cfg(feature = "gzip")]
+        Some(Compression::Gzip) => {
+            Box::pin(async_compression::tokio::write::GzipEncoder::new(writer))
+        }
+        #[cfg(feature = "zstd")]
+        Some(Compression::Zstd) => {
+            Box::pin(async_compression::tokio::write::ZstdEncoder::new(writer))
+        }
+        #[cfg(feature = "xz")]
+        Some(Compression::Xz) => Box::pin(async_compression::tokio::write::XzEncoder::new(writer)),
+    }
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_decode_gzip() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"compressed").unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut output = String::new();
+    decode(io::Cursor::new(compressed.clone()), CompressionMode::Auto)
+        .unwrap()
+        .read_to_string(&mut output)
+        .unwrap();
+    assert_eq!(output, "compressed");
+
+    let mut output = Vec::new();
+    decode(
+        io::Cursor::new(compressed.clone()),
+        CompressionMode::Disabled,
+    )
+    .unwrap()
+    .read_to_end(&mut output)
+    .unwrap();
+    assert_eq!(output, compressed);
+
+    let mut output = String::new();
+    decode(io::Cursor::new(b"plain".to_vec()), CompressionMode::Auto)
+        .unwrap()
+        .read_to_string(&mut output)
+        .unwrap();
+    assert_eq!(output, "plain");
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_encode_zstd() {
+    let mut compressed = Vec::new();
+    let mut encoder =
+        Encoder::new(&mut compressed, Compression::from_extension("out.json.zst")).unwrap();
+    encoder.write_all(b"compressed").unwrap();
+    drop(encoder);
+    assert_eq!(
+        Compression::from_magic(&compressed),
+        Some(Compression::Zstd)
+    );
+
+    let mut output = String::new();
+    decode(io::Cursor::new(compressed), CompressionMode::Auto)
+        .unwrap()
+        .read_to_string(&mut output)
+        .unwrap();
+    assert_eq!(output, "compressed");
+}
@@ -0,0 +1,459 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, RwLock};
+
+use super::StdinError;
+
+static GLOBAL: LazyLock<StdinContext> = LazyLock::new(StdinContext::new);
+
+thread_local! {
+    static CURRENT: RefCell<Option<StdinContext>> = const { RefCell::new(None) };
+}
+
+/// What to do when reading `-` while `stdin` is an interactive terminal, rather than piped data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TerminalPolicy {
+    /// Fail with [`StdinError::StdinIsTerminal`] instead of waiting for input that may never come
+    #[default]
+    Refuse,
+    /// Read from the terminal until EOF (Ctrl-D)
+    Allow,
+    /// Print the prompt on `stderr` and read a single line for [`MaybeStdin`](crate::MaybeStdin) values
+    ///
+    /// Since only a line is read, several `MaybeStdin` values can be prompted for.
+    /// Other types read from the terminal until EOF, as with [`TerminalPolicy::Allow`].
+    /// To give each arg its own prompt, see
+    /// [`MaybeStdin::parse_with_prompt`](crate::MaybeStdin::parse_with_prompt).
+    Prompt(&'static str),
+}
+
+/// State shared by every value reading from `stdin`: whether it has been read, and how it may be read
+///
+/// By default all values use a single process-wide context, so `stdin` can only be read once per
+/// process. Programs that parse args more than once (REPLs, multicall binaries, in-process tests)
+/// can [`reset`](StdinContext::reset) the context, or [`enter`](StdinContext::enter) a separate
+/// context for the current thread:
+///
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{MaybeStdin, StdinContext};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     value: MaybeStdin<String>,
+/// }
+///
+/// let context = StdinContext::new();
+/// let _guard = context.enter();
+/// let first = Args::parse_from(["repl", "-"]);
+/// context.reset();
+/// let second = Args::parse_from(["repl", "-"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StdinContext(Arc<ContextState>);
+
+#[derive(Debug)]
+struct ContextState {
+    has_been_read: AtomicBool,
+    max_size: AtomicU64,
+    terminal_policy: RwLock<TerminalPolicy>,
+    stdin_sentinels: RwLock<Vec<String>>,
+    stdout_sentinels: RwLock<Vec<String>>,
+    claimed_fds: Mutex<HashSet<u32>>,
+    #[cfg(feature = "testing")]
+    fake: Mutex<super::testing::FakeStreams>,
+}
+
+impl Default for ContextState {
+    fn default() -> Self {
+        Self {
+            has_been_read: AtomicBool::new(false),
+            max_size: AtomicU64::new(u64::MAX),
+            terminal_policy: RwLock::new(TerminalPolicy::default()),
+            stdin_sentinels: RwLock::new(vec![String::from("-")]),
+            stdout_sentinels: RwLock::new(vec![String::from("-")]),
+            claimed_fds: Mutex::new(HashSet::new()),
+            #[cfg(feature = "testing")]
+            fake: Default::default(),
+        }
+    }
+}
+
+impl StdinContext {
+    /// Create a context where `stdin` hasn't been read, with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide context, used unless another context has been entered
+    pub fn global() -> Self {
+        GLOBAL.clone()
+    }
+
+    /// The context used by the current thread
+    pub fn current() -> Self {
+        CURRENT
+            .with_borrow(|current| current.clone())
+            .unwrap_or_else(Self::global)
+    }
+
+    /// Use this context on the current thread until the guard is dropped
+    ///
+    /// Since the context is per-thread, async tasks should enter it from the thread they run on.
+    pub fn enter(&self) -> StdinContextGuard {
+        let previous = CURRENT.with_borrow_mut(|current| current.replace(self.clone()));
+        StdinContextGuard {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Has `stdin` been read in this context
+    pub fn has_been_read(&self) -> bool {
+        self.0.has_been_read.load(Ordering::Acquire)
+    }
+
+    /// Allow `stdin` (and any inherited file descriptors) to be used again
+    pub fn reset(&self) {
+        self.0.has_been_read.store(false, Ordering::Release);
+        self.claimed_fds().clear();
+    }
+
+    fn claimed_fds(&self) -> MutexGuard<'_, HashSet<u32>> {
+        self.0.claimed_fds.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The size limit for values read from `stdin` by [`MaybeStdin`](crate::MaybeStdin)
+    pub fn max_size(&self) -> Option<u64> {
+        match self.0.max_size.load(Ordering::Acquire) {
+            u64::MAX => None,
+            max_size => Some(max_size),
+        }
+    }
+
+    /// Limit the size of values read from `stdin` by [`MaybeStdin`](crate::MaybeStdin),
+    /// failing with [`StdinError::SizeLimit`] when exceeded (default: unlimited)
+    pub fn set_max_size(&self, max_size: Option<u64>) {
+        self.0
+            .max_size
+            .store(max_size.unwrap_or(u64::MAX), Ordering::Release);
+    }
+
+    /// What happens when `stdin` is read while it's an interactive terminal
+    pub fn terminal_policy(&self) -> TerminalPolicy {
+        *self
+            .0
+            .terminal_policy
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Choose what happens when `stdin` is read while it's an interactive terminal
+    /// (default: [`TerminalPolicy::Refuse`])
+    pub fn set_terminal_policy(&self, policy: TerminalPolicy) {
+        *self
+            .0
+            .terminal_policy
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = policy;
+    }
+
+    /// Set the arg values that refer to `stdin` rather than a file (default: `-`)
+    ///
+    /// Values are matched exactly, so a file named like a sentinel can still be passed with a
+    /// path prefix (e.g. `./-`). These apply to every type reading from `stdin`, including
+    /// [`MaybeStdin`](crate::MaybeStdin) values; use `parse_with_sentinels` (e.g.
+    /// [`FileOrStdin::parse_with_sentinels`](crate::FileOrStdin::parse_with_sentinels)) as the
+    /// value parser of an arg to give it its own sentinels.
+    pub fn set_stdin_sentinels<S: Into<String>>(&self, sentinels: impl IntoIterator<Item = S>) {
+        *self
+            .0
+            .stdin_sentinels
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = sentinels.into_iter().map(Into::into).collect();
+    }
+
+    /// Set the arg values that refer to `stdout` rather than a file (default: `-`)
+    ///
+    /// As with [`StdinContext::set_stdin_sentinels`], values are matched exactly.
+    pub fn set_stdout_sentinels<S: Into<String>>(&self, sentinels: impl IntoIterator<Item = S>) {
+        *self
+            .0
+            .stdout_sentinels
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = sentinels.into_iter().map(Into::into).collect();
+    }
+
+    /// Does the arg value refer to `stdin`
+    pub fn is_stdin_sentinel(&self, value: &str) -> bool {
+        let sentinels = self.0.stdin_sentinels.read();
+        sentinels
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|sentinel| sentinel == value)
+    }
+
+    /// Does the arg value refer to `stdout`
+    pub fn is_stdout_sentinel(&self, value: &str) -> bool {
+        let sentinels = self.0.stdout_sentinels.read();
+        sentinels
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|sentinel| sentinel == value)
+    }
+
+    /// Install in-memory contents to be read in place of `stdin`, treated as piped data
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    ///
+    /// use clap::Parser;
+    /// use clap_stdin::{FileOrStdout, MaybeStdin, StdinContext};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     value: MaybeStdin<String>,
+    ///     output: FileOrStdout,
+    /// }
+    ///
+    /// let context = StdinContext::new();
+    /// context.set_stdin("piped value");
+    /// context.capture_stdout();
+    /// let _guard = context.enter();
+    ///
+    /// let args = Args::try_parse_from(["app", "-", "-"]).unwrap();
+    /// assert_eq!(*args.value, "piped value");
+    /// args.output.into_writer().unwrap().write_all(b"output").unwrap();
+    /// assert_eq!(context.take_stdout(), b"output");
+    /// ```
+    #[cfg(feature = "testing")]
+    pub fn set_stdin(&self, contents: impl Into<Vec<u8>>) {
+        self.fake().stdin = Some(contents.into().into());
+    }
+
+    /// Pretend `stdin` is (or isn't) an interactive terminal
+    #[cfg(feature = "testing")]
+    pub fn set_terminal(&self, terminal: bool) {
+        self.fake().terminal = Some(terminal);
+    }
+
+    /// Capture output written to `stdout` by [`FileOrStdout`](crate::FileOrStdout), to be
+    /// retrieved with [`StdinContext::take_stdout`]
+    #[cfg(feature = "testing")]
+    pub fn capture_stdout(&self) {
+        self.fake().stdout.get_or_insert_with(Default::default);
+    }
+
+    /// Take the output captured since [`StdinContext::capture_stdout`] (or the last call)
+    #[cfg(feature = "testing")]
+    pub fn take_stdout(&self) -> Vec<u8> {
+        self.fake()
+            .stdout
+            .as_ref()
+            .map(|capture| capture.take())
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "testing")]
+    fn fake(&self) -> MutexGuard<'_, super::testing::FakeStreams> {
+        self.0.fake.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn stdin_is_terminal(&self) -> bool {
+        #[cfg(feature = "testing")]
+        if let Some(terminal) = self.fake().stdin_is_terminal() {
+            return terminal;
+        }
+        io::stdin().is_terminal()
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn stdout_is_terminal(&self) -> bool {
+        #[cfg(feature = "testing")]
+        if self.fake().stdout.is_some() {
+            return false;
+        }
+        io::stdout().is_terminal()
+    }
+
+    /// Reader for `stdin`, which doesn't hold the lock so other reads of `stdin` can't deadlock
+    pub(crate) fn stdin(&self) -> Box<dyn io::Read> {
+        #[cfg(feature = "testing")]
+        if let Some(contents) = self.fake().stdin.clone() {
+            return Box::new(io::Cursor::new(contents));
+        }
+        Box::new(io::stdin())
+    }
+
+    /// Locked `stdin` for reading lines, which should be dropped as soon as they are read
+    pub(crate) fn stdin_lock(&self) -> Box<dyn io::BufRead> {
+        #[cfg(feature = "testing")]
+        if let Some(contents) = self.fake().stdin.clone() {
+            return Box::new(io::Cursor::new(contents));
+        }
+        Box::new(io::stdin().lock())
+    }
+
+    pub(crate) fn stdout(&self) -> Box<dyn io::Write> {
+        #[cfg(feature = "testing")]
+        if let Some(capture) = self.fake().stdout.clone() {
+            return Box::new(capture);
+        }
+        Box::new(io::stdout())
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn async_stdin(&self) -> std::pin::Pin<Box<dyn tokio::io::AsyncRead>> {
+        #[cfg(feature = "testing")]
+        if let Some(contents) = self.fake().stdin.clone() {
+            return Box::pin(io::Cursor::new(contents));
+        }
+        Box::pin(tokio::io::stdin())
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn async_stdout(&self) -> std::pin::Pin<Box<dyn tokio::io::AsyncWrite>> {
+        #[cfg(feature = "testing")]
+        if let Some(capture) = self.fake().stdout.clone() {
+            return Box::pin(capture);
+        }
+        Box::pin(tokio::io::stdout())
+    }
+
+    /// Mark an inherited file descriptor as used, so it can't be used again
+    pub(crate) fn claim_fd(&self, fd: u32) -> Result<(), StdinError> {
+        if !self.claimed_fds().insert(fd) {
+            return Err(StdinError::FdRepeatedUse(fd));
+        }
+        Ok(())
+    }
+
+    /// Check that `stdin` can be read, and mark it as read so it can't be used again
+    pub(crate) fn claim(&self) -> Result<(), StdinError> {
+        if self.terminal_policy() == TerminalPolicy::Refuse && self.stdin_is_terminal() {
+            return Err(StdinError::StdinIsTerminal);
+        }
+        if self.0.has_been_read.swap(true, Ordering::AcqRel) {
+            return Err(StdinError::StdInRepeatedUse);
+        }
+        Ok(())
+    }
+}
+
+/// Guard returned by [`StdinContext::enter`], restoring the previous context when dropped
+#[derive(Debug)]
+pub struct StdinContextGuard {
+    previous: Option<StdinContext>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for StdinContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with_borrow_mut(|current| *current = previous);
+    }
+}
+
+/// Choose what happens when `stdin` is read while it's an interactive terminal
+/// (default: [`TerminalPolicy::Refuse`])
+///
+/// This sets the policy of the [current](StdinContext::current) context, and must be called
+/// before parsing the args for [`MaybeStdin`](crate::MaybeStdin) values.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{MaybeStdin, TerminalPolicy};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     token: MaybeStdin<String>,
+/// }
+///
+/// clap_stdin::set_terminal_policy(TerminalPolicy::Prompt("Token: "));
+/// let args = Args::parse();
+/// ```
+pub fn set_terminal_policy(policy: TerminalPolicy) {
+    StdinContext::current().set_terminal_policy(policy);
+}
+
+/// Limit the size of values read from `stdin` by [`MaybeStdin`](crate::MaybeStdin), failing with
+/// [`StdinError::SizeLimit`] when exceeded (default: unlimited)
+///
+/// This sets the limit of the [current](StdinContext::current) context. Since `MaybeStdin` values
+/// are read while parsing the args, it must be called before parsing. See
+/// [`FileOrStdin::with_max_size`](crate::FileOrStdin::with_max_size) to limit the size of a
+/// `FileOrStdin` input.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdin;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     value: MaybeStdin<String>,
+/// }
+///
+/// clap_stdin::set_max_stdin_size(Some(64 * 1024));
+/// let args = Args::parse();
+/// ```
+pub fn set_max_stdin_size(max_size: Option<u64>) {
+    StdinContext::current().set_max_size(max_size);
+}
+
+/// Set the arg values that refer to `stdin` rather than a file (default: `-`)
+///
+/// This sets the sentinels of the [current](StdinContext::current) context, and must be called
+/// before parsing the args. Values are matched exactly, so a file named like a sentinel can still
+/// be passed with a path prefix (e.g. `./-`). The sentinels apply to every arg, including
+/// [`MaybeStdin`](crate::MaybeStdin) values (see [`StdinContext::set_stdin_sentinels`]).
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::FileOrStdin;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: FileOrStdin,
+/// }
+///
+/// clap_stdin::set_stdin_sentinels(["-", "/dev/stdin"]);
+/// let args = Args::parse();
+/// ```
+pub fn set_stdin_sentinels<S: Into<String>>(sentinels: impl IntoIterator<Item = S>) {
+    StdinContext::current().set_stdin_sentinels(sentinels);
+}
+
+/// Set the arg values that refer to `stdout` rather than a file (default: `-`)
+///
+/// This sets the sentinels of the [current](StdinContext::current) context, and must be called
+/// before parsing the args. See [`set_stdin_sentinels`].
+pub fn set_stdout_sentinels<S: Into<String>>(sentinels: impl IntoIterator<Item = S>) {
+    StdinContext::current().set_stdout_sentinels(sentinels);
+}
+
+#[test]
+fn test_stdin_context() {
+    let context = StdinContext::new();
+    context.set_terminal_policy(TerminalPolicy::Allow);
+    {
+        let _guard = context.enter();
+        let current = StdinContext::current();
+        assert!(Arc::ptr_eq(&current.0, &context.0));
+        current.claim().unwrap();
+        assert!(context.has_been_read());
+        assert!(matches!(current.claim(), Err(StdinError::StdInRepeatedUse)));
+        context.reset();
+        current.claim().unwrap();
+
+        let nested = StdinContext::new();
+        nested.set_terminal_policy(TerminalPolicy::Allow);
+        let guard = nested.enter();
+        StdinContext::current().claim().unwrap();
+        drop(guard);
+        assert!(Arc::ptr_eq(&StdinContext::current().0, &context.0));
+    }
+    assert!(Arc::ptr_eq(
+        &StdinContext::current().0,
+        &StdinContext::global().0
+    ));
+}
@@ -0,0 +1,282 @@
+use std::str::FromStr;
+
+use super::{Source, StdinContext, StdinError};
+
+/// Where a value resolved by a [`Fallback`] chain came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// The arg passed on the command line
+    Arg,
+    /// The environment variable
+    Env,
+    /// Data piped to `stdin`
+    Stdin,
+    /// The default value
+    Default,
+}
+
+/// Resolution order for a value that may come from several places: the arg, then an environment
+/// variable, then data piped to `stdin`, then a default value
+///
+/// Each step after the arg is optional, and is tried in that order when the previous steps don't
+/// provide a value. Empty environment variables and empty `stdin` are skipped, and `stdin` is only
+/// read when it isn't an interactive terminal, so a token can be piped in without prompting:
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{Fallback, Origin};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     token: Option<String>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let token = Fallback::new()
+///     .with_env("API_TOKEN")
+///     .with_stdin()
+///     .resolve(args.token)?;
+/// if token.origin() == Origin::Stdin {
+///     eprintln!("using token from stdin");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fallback {
+    env: Option<String>,
+    stdin: bool,
+    default: Option<String>,
+}
+
+impl Fallback {
+    /// Create a chain using only the arg, to add fallbacks to
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fall back to the value of an environment variable
+    pub fn with_env(mut self, name: impl Into<String>) -> Self {
+        self.env = Some(name.into());
+        self
+    }
+
+    /// Fall back to reading data piped to `stdin`
+    pub fn with_stdin(mut self) -> Self {
+        self.stdin = true;
+        self
+    }
+
+    /// Fall back to a default value, parsed like the other values
+    pub fn with_default(mut self, value: impl Into<String>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    /// Resolve the value from the arg (if given) or the first fallback providing a value
+    ///
+    /// Fails with [`StdinError::MissingValue`] when no step provides a value.
+    pub fn resolve<T>(&self, arg: Option<T>) -> Result<Resolved<T>, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        if let Some(inner) = arg {
+            return Ok(Resolved {
+                inner,
+                origin: Origin::Arg,
+            });
+        }
+        if let Some(name) = &self.env
+            && let Some(value) = std::env::var_os(name).filter(|value| !value.is_empty())
+        {
+            let value = value
+                .into_string()
+                .map_err(|_| StdinError::FromStr(format!("${name} is not valid unicode")))?;
+            return Resolved::parse(&value, Origin::Env);
+        }
+        if self.stdin && !StdinContext::current().stdin_is_terminal() {
+            let value = Source::Stdin.get_value()?;
+            if !value.trim().is_empty() {
+                return Resolved::parse(value.trim(), Origin::Stdin);
+            }
+        }
+        if let Some(value) = &self.default {
+            return Resolved::parse(value, Origin::Default);
+        }
+        Err(StdinError::MissingValue(self.steps().join(", ")))
+    }
+
+    /// Describe the fallbacks for help output, e.g. `[fallback: $API_TOKEN, piped stdin]`
+    ///
+    /// Returns an empty string when the chain has no fallbacks.
+    pub fn help(&self) -> String {
+        let steps = self.steps();
+        if steps.len() == 1 {
+            return String::new();
+        }
+        format!("[fallback: {}]", steps[1..].join(", "))
+    }
+
+    /// Add the fallbacks to the help of a clap arg
+    ///
+    /// With the derive API, this can be applied with [`clap::Command::mut_arg`]:
+    /// ```rust,no_run
+    /// use clap::{CommandFactory, FromArgMatches, Parser};
+    /// use clap_stdin::Fallback;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     /// Token for the API
+    ///     #[arg(long)]
+    ///     token: Option<String>,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let fallback = Fallback::new().with_env("API_TOKEN").with_stdin();
+    /// let command = Args::command().mut_arg("token", |arg| fallback.describe(arg));
+    /// let args = Args::from_arg_matches(&command.get_matches())?;
+    /// let token = fallback.resolve(args.token)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ```sh
+    /// $ ./example --help
+    /// Usage: example [OPTIONS]
+    ///
+    /// Options:
+    ///       --token <TOKEN>  Token for the API [fallback: $API_TOKEN, piped stdin]
+    /// ```
+    #[cfg(feature = "clap")]
+    pub fn describe(&self, arg: clap::Arg) -> clap::Arg {
+        if self.help().is_empty() {
+            return arg;
+        }
+        let help = match arg.get_help() {
+            Some(help) => format!("{help} {}", self.help()),
+            None => self.help(),
+        };
+        arg.help(help)
+    }
+
+    /// Each step of the chain, for help and error messages
+    fn steps(&self) -> Vec<String> {
+        let mut steps = vec![String::from("the arg")];
+        if let Some(name) = &self.env {
+            steps.push(format!("${name}"));
+        }
+        if self.stdin {
+            steps.push(String::from("piped stdin"));
+        }
+        if let Some(value) = &self.default {
+            steps.push(format!("default: {value}"));
+        }
+        steps
+    }
+}
+
+/// Value resolved by a [`Fallback`] chain, along with its [`Origin`]
+#[derive(Clone)]
+pub struct Resolved<T> {
+    inner: T,
+    origin: Origin,
+}
+
+impl<T> Resolved<T> {
+    fn parse(value: &str, origin: Origin) -> Result<Self, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        T::from_str(value)
+            .map_err(|e| StdinError::FromStr(format!("{e}")))
+            .map(|inner| Self { inner, origin })
+    }
+
+    /// Where the value came from
+    pub fn origin(&self) -> Origin {
+        self.origin
+    }
+
+    /// Was this value read from stdin
+    pub fn is_stdin(&self) -> bool {
+        self.origin == Origin::Stdin
+    }
+
+    /// Extract the inner value from the wrapper
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> std::fmt::Display for Resolved<T>
+where
+    T: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T> std::fmt::Debug for Resolved<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T> std::ops::Deref for Resolved<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for Resolved<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_fallback() {
+    let context = StdinContext::new();
+    context.set_terminal(true);
+    let _guard = context.enter();
+
+    let fallback = Fallback::new().with_stdin().with_default("7");
+    assert_eq!(fallback.help(), "[fallback: piped stdin, default: 7]");
+    assert_eq!(Fallback::new().help(), "");
+
+    let value = fallback.resolve(Some(1)).unwrap();
+    assert_eq!((*value, value.origin()), (1, Origin::Arg));
+    let value = fallback.resolve::<u32>(None).unwrap();
+    assert_eq!((*value, value.origin()), (7, Origin::Default));
+    assert!(!context.has_been_read());
+
+    context.set_terminal(false);
+    context.set_stdin("");
+    let value = fallback.resolve::<u32>(None).unwrap();
+    assert_eq!(value.origin(), Origin::Default);
+
+    context.reset();
+    context.set_stdin("42\n");
+    let value = fallback.resolve::<u32>(None).unwrap();
+    assert_eq!((*value, value.origin()), (42, Origin::Stdin));
+    assert!(value.is_stdin());
+
+    let err = Fallback::new()
+        .with_env("CLAP_STDIN_UNSET_VAR")
+        .resolve::<u32>(None)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no value provided; tried the arg, $CLAP_STDIN_UNSET_VAR"
+    );
+}
@@ -2,8 +2,15 @@ use std::marker::PhantomData;
 use std::str::FromStr;
 
 #[cfg(feature = "tokio")]
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+#[cfg(feature = "tokio")]
+use tokio_stream::StreamExt;
 
+#[cfg(feature = "compression")]
+use super::CompressionMode;
+use super::limits::{Limited, Limits};
+#[cfg(feature = "serde")]
+use super::{Format, FromReader};
 use super::{Source, StdinError};
 
 /// Wrapper struct to either read in a file or contents from `stdin`
@@ -38,10 +45,50 @@ use super::{Source, StdinError};
 #[derive(Debug, Clone)]
 pub struct FileOrStdin<T = String> {
     source: Source,
+    limits: Limits,
+    /// `None` until chosen, so the byte APIs can leave compressed contents untouched by default
+    #[cfg(feature = "compression")]
+    compression: Option<CompressionMode>,
+    #[cfg(feature = "serde")]
+    format: Option<Format>,
     _type: PhantomData<T>,
 }
 
 impl<T> FileOrStdin<T> {
+    /// Parse the arg, reading from `stdin` only for the given sentinels instead of those of the
+    /// [`StdinContext`](crate::StdinContext)
+    ///
+    /// This can be used as a value parser to give a single arg its own sentinels:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = |s: &str| FileOrStdin::<String>::parse_with_sentinels(s, &["-", "stdin"]))]
+    ///     input: FileOrStdin,
+    /// }
+    ///
+    /// let args = Args::try_parse_from(["example", "stdin"]).unwrap();
+    /// assert!(args.input.is_stdin());
+    /// ```
+    pub fn parse_with_sentinels(s: &str, sentinels: &[&str]) -> Result<Self, StdinError> {
+        Ok(Self::from_source(Source::parse(s, sentinels.contains(&s))))
+    }
+
+    /// Read from an already parsed source, with the default options
+    pub(crate) fn from_source(source: Source) -> Self {
+        Self {
+            source,
+            limits: Limits::default(),
+            #[cfg(feature = "compression")]
+            compression: None,
+            #[cfg(feature = "serde")]
+            format: None,
+            _type: PhantomData,
+        }
+    }
+
     /// Was this value read from stdin
     pub fn is_stdin(&self) -> bool {
         matches!(self.source, Source::Stdin)
@@ -49,17 +96,159 @@ impl<T> FileOrStdin<T> {
 
     /// Was this value read from a file (path passed in from argument values)
     pub fn is_file(&self) -> bool {
-        !self.is_stdin()
+        matches!(self.source, Source::Arg(_))
+    }
+
+    /// The inherited file descriptor read from, if passed as `fd:N` or `/dev/fd/N`
+    pub fn fd(&self) -> Option<u32> {
+        match self.source {
+            Source::Fd { fd, .. } => Some(fd),
+            _ => None,
+        }
     }
 
     /// The value passed to this arg (Either "-" for stdin or a filepath)
     pub fn filename(&self) -> &str {
         match &self.source {
             Source::Stdin => "-",
-            Source::Arg(path) => path,
+            Source::Fd { arg, .. } | Source::Arg(arg) => arg,
         }
     }
 
+    /// Fail with [`StdinError::SizeLimit`] when reading more than `max_size` bytes
+    /// (after decompression) from the input (default: unlimited)
+    ///
+    /// ```no_run
+    /// use clap_stdin::FileOrStdin;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   input: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let contents = args
+    ///     .input
+    ///     .with_max_size(1024 * 1024)
+    ///     .with_max_line_length(4096)
+    ///     .contents()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.limits.max_size = Some(max_size);
+        self
+    }
+
+    /// Fail with [`StdinError::LineTooLong`] when reading a line longer than `max_line_length`
+    /// bytes (excluding the newline) from the input (default: unlimited)
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.limits.max_line_length = Some(max_line_length);
+        self
+    }
+
+    #[cfg(feature = "compression")]
+    /// Choose how the contents should be decompressed when read (default: [`CompressionMode::Auto`],
+    /// which detects gzip/zstd/xz contents from their magic bytes)
+    ///
+    /// ```no_run
+    /// use clap_stdin::{CompressionMode, FileOrStdin};
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   input: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let input = args.input.with_compression(CompressionMode::Disabled).contents()?;
+    /// println!("input={input}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_compression(mut self, mode: CompressionMode) -> Self {
+        self.compression = Some(mode);
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    /// Choose the format to deserialize an [`Auto`](crate::Auto) value with, instead of
+    /// detecting it from the file extension (required when reading from `stdin`)
+    ///
+    /// ```no_run
+    /// use clap::Parser;
+    /// use clap_stdin::{Auto, FileOrStdin, Format};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Clone, Debug, Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    /// }
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     config: FileOrStdin<Auto<Config>>,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let config = args.config.with_format(Format::Yaml).deserialize()?;
+    /// println!("name={}", config.name);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    /// Deserialize the value directly from the input source, with one of the
+    /// [`Json`](crate::Json), [`Yaml`](crate::Yaml), [`Toml`](crate::Toml), or [`Auto`](crate::Auto) wrappers
+    ///
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::{FileOrStdin, Json};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Clone, Debug, Deserialize)]
+    /// struct User {
+    ///     name: String,
+    ///     age: u8,
+    /// }
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     user: FileOrStdin<Json<User>>,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let user = args.user.deserialize()?.into_inner();
+    /// println!("name={}, age={}", user.name, user.age);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deserialize(self) -> Result<T, StdinError>
+    where
+        T: FromReader + 'static,
+    {
+        let format = self.detected_format();
+        let reader = std::io::BufReader::new(self.into_reader()?);
+        T::from_reader(reader, format)
+    }
+
+    #[cfg(feature = "serde")]
+    fn detected_format(&self) -> Option<Format> {
+        self.format.or_else(|| match &self.source {
+            Source::Stdin | Source::Fd { .. } => None,
+            Source::Arg(path) => Format::from_extension(path),
+        })
+    }
+
     /// Read the entire contents from the input source, returning T::from_str
     pub fn contents(self) -> Result<T, StdinError>
     where
@@ -73,6 +262,127 @@ impl<T> FileOrStdin<T> {
         T::from_str(input.trim_end()).map_err(|e| StdinError::FromStr(format!("{e}")))
     }
 
+    /// Read the entire contents from the input source as raw bytes, without
+    /// requiring them to be valid UTF-8 or trimming them
+    ///
+    /// Unlike the other readers, compressed contents are returned untouched unless decompression
+    /// is chosen with [`FileOrStdin::with_compression`] (with a codec feature enabled).
+    pub fn contents_bytes(self) -> Result<Vec<u8>, StdinError> {
+        read_all(self.raw_bytes().into_reader()?)
+    }
+
+    /// Leave compressed contents untouched, unless decompression was chosen
+    fn raw_bytes(self) -> Self {
+        #[cfg(feature = "compression")]
+        if self.compression.is_none() {
+            return self.with_compression(CompressionMode::Disabled);
+        }
+        self
+    }
+
+    /// Read the entire contents from the input source as raw bytes, returning T::try_from
+    ///
+    /// ```no_run
+    /// use clap_stdin::FileOrStdin;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Clone)]
+    /// struct Image(Vec<u8>);
+    ///
+    /// impl TryFrom<Vec<u8>> for Image {
+    ///     type Error = String;
+    ///
+    ///     fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+    ///         match bytes.starts_with(b"\x89PNG") {
+    ///             true => Ok(Self(bytes)),
+    ///             false => Err("not a PNG image".into()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   image: FileOrStdin<Image>,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let image = args.image.contents_from_bytes()?;
+    /// println!("size={}", image.0.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contents_from_bytes(self) -> Result<T, StdinError>
+    where
+        T: TryFrom<Vec<u8>>,
+        <T as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
+    {
+        let input = self.contents_bytes()?;
+        T::try_from(input).map_err(|e| StdinError::FromBytes(format!("{e}")))
+    }
+
+    #[cfg(feature = "mmap")]
+    /// Memory map the contents of a file input, to avoid copying large files onto the heap
+    ///
+    /// Falls back to reading the contents into a buffer for `stdin`, pipes, and other inputs that
+    /// aren't regular files (and for compressed files, which are decompressed into the buffer).
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified (by this or any other process) while the returned contents
+    /// are alive: changes would be visible through the mapped `&[u8]`, and truncating the file
+    /// could crash the program with `SIGBUS` when the contents are read.
+    ///
+    /// ```no_run
+    /// use clap_stdin::FileOrStdin;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   input: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// // SAFETY: the input file isn't modified while the program runs
+    /// let contents = unsafe { args.input.map()? };
+    /// let lines = contents.iter().filter(|&&b| b == b'\n').count();
+    /// println!("lines={lines}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub unsafe fn map(self) -> Result<crate::Mapped, StdinError> {
+        if let Source::Arg(path) = &self.source {
+            let file = std::fs::File::open(path)?;
+            let metadata = file.metadata()?;
+            if let Some(limit) = self.limits.max_size
+                && metadata.len() > limit
+            {
+                return Err(StdinError::SizeLimit { limit });
+            }
+            // Pipes and other special files are read from the handle already opened
+            if !metadata.is_file() || metadata.len() == 0 {
+                return Ok(crate::Mapped::buffer(read_all(self.read_from(file)?)?));
+            }
+            // SAFETY: the caller guarantees the file isn't modified while mapped
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+            #[cfg(feature = "compression")]
+            let compressed = match self.compression.unwrap_or_default() {
+                CompressionMode::Disabled => false,
+                CompressionMode::Forced(_) => true,
+                CompressionMode::Auto => crate::Compression::from_magic(&map).is_some(),
+            };
+            #[cfg(not(feature = "compression"))]
+            let compressed = false;
+            if !compressed {
+                return Ok(crate::Mapped::map(map));
+            }
+            let reader = self.read_from(std::io::Cursor::new(map))?;
+            return Ok(crate::Mapped::buffer(read_all(reader)?));
+        }
+        Ok(crate::Mapped::buffer(read_all(self.into_reader()?)?))
+    }
+
     /// Create a reader from the source, to allow user flexibility of
     /// how to read and parse (e.g. all at once or in chunks)
     ///
@@ -96,7 +406,165 @@ impl<T> FileOrStdin<T> {
     /// # }
     /// ```
     pub fn into_reader(self) -> Result<impl std::io::Read, StdinError> {
-        self.source.into_reader()
+        let reader = self.source.clone().into_reader()?;
+        self.read_from(reader)
+    }
+
+    /// Decompress and limit a reader opened from the source
+    fn read_from<R: std::io::Read + 'static>(
+        self,
+        reader: R,
+    ) -> Result<impl std::io::Read, StdinError> {
+        #[cfg(feature = "compression")]
+        let reader = super::compression::decode(reader, self.compression.unwrap_or_default())?;
+        Ok(Limited::new(reader, self.limits))
+    }
+
+    /// Read the input source line by line, without the trailing newline
+    ///
+    /// See [`FileOrStdin::records`] to parse each line into a value.
+    pub fn lines(self) -> Result<impl Iterator<Item = Result<String, StdinError>>, StdinError> {
+        self.records()
+    }
+
+    /// Read the input source line by line, returning U::from_str for each line
+    ///
+    /// Lines are read through a buffer as the iterator advances, so the whole input
+    /// doesn't need to fit in memory. Parse errors include the (1-based) line number
+    /// with [`StdinError::FromStrLine`].
+    ///
+    /// ```no_run
+    /// use clap_stdin::FileOrStdin;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   input: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut total = 0;
+    /// for value in args.input.records::<u64>()? {
+    ///     total += value?;
+    /// }
+    /// println!("total={total}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn records<U>(self) -> Result<impl Iterator<Item = Result<U, StdinError>>, StdinError>
+    where
+        U: FromStr,
+        <U as FromStr>::Err: std::fmt::Display,
+    {
+        Ok(Records {
+            reader: std::io::BufReader::new(self.into_reader()?),
+            line: 0,
+            _type: PhantomData,
+        })
+    }
+
+    #[cfg(feature = "json")]
+    /// Deserialize each value of newline-delimited JSON (NDJSON) input as it is read
+    ///
+    /// Values may be separated by any whitespace, and the record separators of a JSON text
+    /// sequence (RFC 7464) are also accepted. Only one value is held in memory at a time, and
+    /// errors include the (1-based) record number with [`StdinError::DeserializeRecord`].
+    ///
+    /// ```no_run
+    /// use clap_stdin::FileOrStdin;
+    /// use clap::Parser;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Event {
+    ///   name: String,
+    /// }
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   events: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// for event in args.events.ndjson::<Event>()? {
+    ///     println!("{}", event?.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ndjson<U>(self) -> Result<impl Iterator<Item = Result<U, StdinError>>, StdinError>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        Ok(super::json_stream::Values::new(std::io::BufReader::new(
+            self.into_reader()?,
+        )))
+    }
+
+    #[cfg(feature = "json")]
+    /// Deserialize each element of a top-level JSON array as it is read
+    ///
+    /// Like [`FileOrStdin::ndjson`], only one element is held in memory at a time and
+    /// errors include the (1-based) element number.
+    pub fn json_array<U>(self) -> Result<impl Iterator<Item = Result<U, StdinError>>, StdinError>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        Ok(super::json_stream::Elements::new(std::io::BufReader::new(
+            self.into_reader()?,
+        )))
+    }
+
+    #[cfg(feature = "csv")]
+    /// Deserialize each row of CSV input as it is read, with the default [`CsvOptions`](crate::CsvOptions)
+    /// (comma-separated, or tab-separated for `.tsv` files, with a header row)
+    ///
+    /// ```no_run
+    /// use clap_stdin::FileOrStdin;
+    /// use clap::Parser;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Row {
+    ///   name: String,
+    ///   score: u32,
+    /// }
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   input: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// for row in args.input.csv_records::<Row>()? {
+    ///     let row = row?;
+    ///     println!("{}: {}", row.name, row.score);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn csv_records<U>(self) -> Result<impl Iterator<Item = Result<U, StdinError>>, StdinError>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        self.csv_records_with(crate::CsvOptions::default())
+    }
+
+    #[cfg(feature = "csv")]
+    /// Deserialize each row of CSV input as it is read, with the given delimiter and header options
+    pub fn csv_records_with<U>(
+        self,
+        options: crate::CsvOptions,
+    ) -> Result<impl Iterator<Item = Result<U, StdinError>>, StdinError>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        let filename = self.filename().to_owned();
+        let reader = options.reader(&filename, self.into_reader()?);
+        Ok(super::tabular::records(reader))
     }
 
     #[cfg(feature = "tokio")]
@@ -128,6 +596,92 @@ impl<T> FileOrStdin<T> {
         T::from_str(input.trim_end()).map_err(|e| StdinError::FromStr(format!("{e}")))
     }
 
+    #[cfg(feature = "tokio")]
+    /// Async version of [`FileOrStdin::contents_bytes`]
+    pub async fn contents_bytes_async(self) -> Result<Vec<u8>, StdinError> {
+        let mut reader = self.raw_bytes().open_async().await?;
+        let mut input = Vec::new();
+        let _ = reader.read_to_end(&mut input).await?;
+        Ok(input)
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Async version of [`FileOrStdin::contents_from_bytes`]
+    pub async fn contents_from_bytes_async(self) -> Result<T, StdinError>
+    where
+        T: TryFrom<Vec<u8>>,
+        <T as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
+    {
+        let input = self.contents_bytes_async().await?;
+        T::try_from(input).map_err(|e| StdinError::FromBytes(format!("{e}")))
+    }
+
+    #[cfg(all(feature = "tokio", feature = "serde"))]
+    /// Async version of [`FileOrStdin::deserialize`]
+    ///
+    /// The contents are read into memory before being deserialized.
+    pub async fn deserialize_async(self) -> Result<T, StdinError>
+    where
+        T: FromReader,
+    {
+        let format = self.detected_format();
+        let mut reader = self.open_async().await?;
+        let mut input = Vec::new();
+        let _ = reader.read_to_end(&mut input).await?;
+        T::from_reader(input.as_slice(), format)
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Async version of [`FileOrStdin::lines`], as a [`Stream`](tokio_stream::Stream) of lines
+    pub async fn lines_async(
+        self,
+    ) -> Result<impl tokio_stream::Stream<Item = Result<String, StdinError>>, StdinError> {
+        self.records_async().await
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Async version of [`FileOrStdin::records`], as a [`Stream`](tokio_stream::Stream) of
+    /// U::from_str for each line
+    ///
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::FileOrStdin;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     input: FileOrStdin,
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut records = std::pin::pin!(args.input.records_async::<u64>().await?);
+    /// while let Some(value) = records.next().await {
+    ///     println!("value={}", value?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn records_async<U>(
+        self,
+    ) -> Result<impl tokio_stream::Stream<Item = Result<U, StdinError>>, StdinError>
+    where
+        U: FromStr,
+        <U as FromStr>::Err: std::fmt::Display,
+    {
+        let reader = tokio::io::BufReader::new(self.open_async().await?);
+        let lines = tokio_stream::wrappers::LinesStream::new(reader.lines());
+        let mut line = 0;
+        Ok(lines.map(move |result| {
+            line += 1;
+            U::from_str(&result?).map_err(|e| StdinError::FromStrLine {
+                line,
+                error: format!("{e}"),
+            })
+        }))
+    }
+
     #[cfg(feature = "tokio")]
     /// Create a reader from the source, to allow user flexibility of
     /// how to read and parse (e.g. all at once or in chunks)
@@ -154,14 +708,51 @@ impl<T> FileOrStdin<T> {
     /// # }
     /// ```
     pub async fn into_async_reader(&self) -> Result<impl tokio::io::AsyncRead, StdinError> {
-        let input: std::pin::Pin<Box<dyn tokio::io::AsyncRead + 'static>> = match &self.source {
-            Source::Stdin => Box::pin(tokio::io::stdin()),
-            Source::Arg(filepath) => {
-                let f = tokio::fs::File::open(filepath).await?;
-                Box::pin(f)
-            }
-        };
-        Ok(input)
+        self.open_async().await
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn open_async(&self) -> Result<std::pin::Pin<Box<dyn tokio::io::AsyncRead>>, StdinError> {
+        let input = self.source.clone().into_async_reader().await?;
+        #[cfg(feature = "compression")]
+        let input =
+            super::compression::decode_async(input, self.compression.unwrap_or_default()).await?;
+        Ok(Box::pin(Limited::new(input, self.limits)))
+    }
+}
+
+/// Iterator parsing each line of a reader with U::from_str
+struct Records<R, U> {
+    reader: R,
+    line: usize,
+    _type: PhantomData<U>,
+}
+
+impl<R, U> Iterator for Records<R, U>
+where
+    R: std::io::BufRead,
+    U: FromStr,
+    <U as FromStr>::Err: std::fmt::Display,
+{
+    type Item = Result<U, StdinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = String::new();
+        let read = self.reader.read_line(&mut buf);
+        if matches!(read, Ok(0)) {
+            return None;
+        }
+        // A line that fails to read (e.g. invalid UTF-8) still counts towards the line numbers
+        self.line += 1;
+        if let Err(e) = read {
+            return Some(Err(e.into()));
+        }
+        let line = buf.strip_suffix('\n').unwrap_or(&buf);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        Some(U::from_str(line).map_err(|e| StdinError::FromStrLine {
+            line: self.line,
+            error: format!("{e}"),
+        }))
     }
 }
 
@@ -169,14 +760,17 @@ impl<T> FromStr for FileOrStdin<T> {
     type Err = StdinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let source = Source::from_str(s)?;
-        Ok(Self {
-            source,
-            _type: PhantomData,
-        })
+        Ok(Self::from_source(Source::from_str(s)?))
     }
 }
 
+/// Read everything from the reader, keeping the limit errors it raises
+fn read_all(mut reader: impl std::io::Read) -> Result<Vec<u8>, StdinError> {
+    let mut input = Vec::new();
+    let _ = reader.read_to_end(&mut input)?;
+    Ok(input)
+}
+
 #[test]
 fn test_source_methods() {
     let val: FileOrStdin<String> = "-".parse().unwrap();
@@ -189,3 +783,206 @@ fn test_source_methods() {
     assert!(!val.is_stdin());
     assert_eq!(val.filename(), "/path/to/something");
 }
+
+#[test]
+fn test_records() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "1\r\n2\nthree\n4").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let val: FileOrStdin = path.parse().unwrap();
+    let lines: Vec<_> = val.lines().unwrap().map(Result::unwrap).collect();
+    assert_eq!(lines, ["1", "2", "three", "4"]);
+
+    let val: FileOrStdin = path.parse().unwrap();
+    let records: Vec<_> = val.records::<u32>().unwrap().collect();
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[1].as_ref().unwrap(), &2);
+    assert!(matches!(
+        &records[2],
+        Err(StdinError::FromStrLine { line: 3, .. })
+    ));
+    assert_eq!(records[3].as_ref().unwrap(), &4);
+
+    std::fs::write(&tmp, b"1\n\xff\nthree\n").unwrap();
+    let val: FileOrStdin = path.parse().unwrap();
+    let records: Vec<_> = val.records::<u32>().unwrap().collect();
+    assert!(matches!(&records[1], Err(StdinError::StdIn(_))));
+    assert!(matches!(
+        &records[2],
+        Err(StdinError::FromStrLine { line: 3, .. })
+    ));
+}
+
+#[cfg(all(test, feature = "tokio"))]
+#[tokio::test]
+async fn test_records_async() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "1\r\n2\nthree\n4").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let val: FileOrStdin = path.parse().unwrap();
+    let lines: Vec<_> = val.lines_async().await.unwrap().collect().await;
+    let lines: Vec<_> = lines.into_iter().map(Result::unwrap).collect();
+    assert_eq!(lines, ["1", "2", "three", "4"]);
+
+    let val: FileOrStdin = path.parse().unwrap();
+    let records: Vec<_> = val.records_async::<u32>().await.unwrap().collect().await;
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[1].as_ref().unwrap(), &2);
+    assert!(matches!(
+        &records[2],
+        Err(StdinError::FromStrLine { line: 3, .. })
+    ));
+    assert_eq!(records[3].as_ref().unwrap(), &4);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_deserialize() {
+    use crate::{Auto, Json};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("values.json");
+    std::fs::write(&path, r#"{"a": 1, "b": 2}"#).unwrap();
+    let path = path.to_str().unwrap();
+
+    let val: FileOrStdin<Json<std::collections::BTreeMap<String, u32>>> = path.parse().unwrap();
+    assert_eq!(val.deserialize().unwrap()["b"], 2);
+
+    let val: FileOrStdin<Auto<std::collections::BTreeMap<String, u32>>> = path.parse().unwrap();
+    assert_eq!(val.deserialize().unwrap().len(), 2);
+
+    let val: FileOrStdin<Json<Vec<u32>>> = path.parse().unwrap();
+    assert!(matches!(val.deserialize(), Err(StdinError::Deserialize(_))));
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_csv_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("scores.tsv");
+    std::fs::write(&path, "name\tscore\nalice\t3\nbob\t5\n").unwrap();
+
+    let val: FileOrStdin = path.to_str().unwrap().parse().unwrap();
+    let rows: Vec<(String, u32)> = val
+        .csv_records()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows, [("alice".to_owned(), 3), ("bob".to_owned(), 5)]);
+
+    let val: FileOrStdin = path.to_str().unwrap().parse().unwrap();
+    let rows: Result<Vec<(String, u32)>, _> =
+        val.with_max_size(16).csv_records().unwrap().collect();
+    assert!(matches!(rows, Err(StdinError::SizeLimit { limit: 16 })));
+}
+
+#[test]
+fn test_contents_bytes() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let bytes = [0xff, 0xfe, b'\n', 0x00, b' '];
+    std::fs::write(&tmp, bytes).unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let val: FileOrStdin = path.parse().unwrap();
+    assert_eq!(val.contents_bytes().unwrap(), bytes);
+
+    let val: FileOrStdin<Vec<u8>> = path.parse().unwrap();
+    assert_eq!(val.contents_from_bytes().unwrap(), bytes);
+
+    struct Utf8(#[allow(dead_code)] String);
+    impl TryFrom<Vec<u8>> for Utf8 {
+        type Error = std::string::FromUtf8Error;
+
+        fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+            String::from_utf8(bytes).map(Self)
+        }
+    }
+
+    let val: FileOrStdin<Utf8> = path.parse().unwrap();
+    assert!(matches!(
+        val.contents_from_bytes(),
+        Err(StdinError::FromBytes(_))
+    ));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_contents_bytes_compressed() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"binary").unwrap();
+    let compressed = encoder.finish().unwrap();
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, &compressed).unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let val: FileOrStdin = path.parse().unwrap();
+    assert_eq!(val.contents_bytes().unwrap(), compressed);
+    let val: FileOrStdin = path.parse().unwrap();
+    let val = val.with_compression(CompressionMode::Auto);
+    assert_eq!(val.contents_bytes().unwrap(), b"binary");
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_map() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "mapped\n").unwrap();
+    let val: FileOrStdin = tmp.path().to_str().unwrap().parse().unwrap();
+    let contents = unsafe { val.map() }.unwrap();
+    assert!(contents.is_mapped());
+    assert_eq!(contents.as_str().unwrap(), "mapped\n");
+
+    let empty = tempfile::NamedTempFile::new().unwrap();
+    let val: FileOrStdin = empty.path().to_str().unwrap().parse().unwrap();
+    let contents = unsafe { val.map() }.unwrap();
+    assert!(!contents.is_mapped());
+    assert!(contents.is_empty());
+
+    #[cfg(unix)]
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("fifo");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let writer = {
+            let fifo = fifo.clone();
+            std::thread::spawn(move || std::fs::write(fifo, "piped\n"))
+        };
+        let val: FileOrStdin = fifo.to_str().unwrap().parse().unwrap();
+        let contents = unsafe { val.map() }.unwrap();
+        assert!(!contents.is_mapped());
+        assert_eq!(contents.as_str().unwrap(), "piped\n");
+        writer.join().unwrap().unwrap();
+    }
+}
+
+#[test]
+fn test_limits() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "1\n22\n333\n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let val: FileOrStdin = path.parse().unwrap();
+    assert_eq!(val.with_max_size(9).contents().unwrap(), "1\n22\n333");
+    let val: FileOrStdin = path.parse().unwrap();
+    assert!(matches!(
+        val.with_max_size(8).contents(),
+        Err(StdinError::SizeLimit { limit: 8 })
+    ));
+
+    let val: FileOrStdin = path.parse().unwrap();
+    let lines: Vec<_> = val.with_max_line_length(2).lines().unwrap().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1].as_ref().unwrap(), "22");
+    assert!(matches!(
+        lines[2],
+        Err(StdinError::LineTooLong { line: 3, limit: 2 })
+    ));
+}
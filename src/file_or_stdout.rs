@@ -1,6 +1,14 @@
 use std::str::FromStr;
 
-use super::Dest;
+#[cfg(all(feature = "atomic", feature = "tokio"))]
+use super::AsyncAtomicWriter;
+#[cfg(feature = "atomic")]
+use super::AtomicWriter;
+#[cfg(feature = "compression")]
+use super::CompressionMode;
+#[cfg(feature = "serde")]
+use super::Format;
+use super::{Dest, OutputWriter, WriteMode};
 
 /// `FileOrStdout` can be used as a proxy output writer to write to whichever destination
 /// was specified by the CLI args, a file or `stdout`.
@@ -34,9 +42,44 @@ use super::Dest;
 #[derive(Debug, Clone)]
 pub struct FileOrStdout {
     dest: Dest,
+    mode: WriteMode,
+    overwrite: OverwritePolicy,
+    #[cfg(feature = "compression")]
+    compression: CompressionMode,
 }
 
 impl FileOrStdout {
+    /// Parse the arg, writing to `stdout` only for the given sentinels instead of those of the
+    /// [`StdinContext`](crate::StdinContext)
+    ///
+    /// This can be used as a value parser to give a single arg its own sentinels:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::FileOrStdout;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = |s: &str| FileOrStdout::parse_with_sentinels(s, &["-", "stdout"]))]
+    ///     output: FileOrStdout,
+    /// }
+    ///
+    /// let args = Args::try_parse_from(["example", "stdout"]).unwrap();
+    /// assert!(args.output.is_stdout());
+    /// ```
+    pub fn parse_with_sentinels(s: &str, sentinels: &[&str]) -> std::io::Result<Self> {
+        Ok(Self::from_dest(Dest::parse(s, sentinels.contains(&s))))
+    }
+
+    fn from_dest(dest: Dest) -> Self {
+        Self {
+            dest,
+            mode: WriteMode::default(),
+            overwrite: OverwritePolicy::default(),
+            #[cfg(feature = "compression")]
+            compression: CompressionMode::default(),
+        }
+    }
+
     /// Was this value read from stdout
     pub fn is_stdout(&self) -> bool {
         matches!(self.dest, Dest::Stdout)
@@ -44,20 +87,155 @@ impl FileOrStdout {
 
     /// Was this value read from a file (path passed in from argument values)
     pub fn is_file(&self) -> bool {
-        !self.is_stdout()
+        matches!(self.dest, Dest::Arg(_))
+    }
+
+    /// The inherited file descriptor written to, if passed as `fd:N` or `/dev/fd/N`
+    pub fn fd(&self) -> Option<u32> {
+        match self.dest {
+            Dest::Fd { fd, .. } => Some(fd),
+            _ => None,
+        }
     }
 
     /// The value passed to this arg (Either "-" for stdout or a filepath)
     pub fn filename(&self) -> &str {
         match &self.dest {
             Dest::Stdout => "-",
-            Dest::Arg(path) => path,
+            Dest::Fd { arg, .. } | Dest::Arg(arg) => arg,
+        }
+    }
+
+    /// Append to the destination file instead of truncating it (default: `false`)
+    ///
+    /// See [`FileOrStdoutAppend`] for an arg type that appends by default.
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.mode = if append {
+            WriteMode::Append
+        } else {
+            WriteMode::Truncate
+        };
+        self
+    }
+
+    /// Will writes be appended to the destination file
+    pub fn is_append(&self) -> bool {
+        self.mode == WriteMode::Append
+    }
+
+    /// Choose what happens when the destination file already exists (default: [`OverwritePolicy::Force`])
+    ///
+    /// This has no effect when appending, since existing contents are kept.
+    ///
+    /// ```no_run
+    /// use std::io::Write;
+    ///
+    /// use clap_stdin::{FileOrStdout, OverwritePolicy};
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   output: FileOrStdout,
+    ///   /// Overwrite the output file if it exists
+    ///   #[arg(long)]
+    ///   force: bool,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut writer = args
+    ///     .output
+    ///     .with_overwrite(OverwritePolicy::Prompt)
+    ///     .with_force(args.force)
+    ///     .into_writer()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_overwrite(mut self, policy: OverwritePolicy) -> Self {
+        self.overwrite = policy;
+        self
+    }
+
+    /// Overwrite an existing destination file regardless of the [`OverwritePolicy`] when `force`
+    /// is `true` (e.g. from a `--force` flag), otherwise keep the current policy
+    pub fn with_force(self, force: bool) -> Self {
+        if force {
+            self.with_overwrite(OverwritePolicy::Force)
+        } else {
+            self
+        }
+    }
+
+    /// Resolve the [`OverwritePolicy`] into how the destination file should be opened
+    fn write_mode(&self) -> std::io::Result<WriteMode> {
+        let Dest::Arg(path) = &self.dest else {
+            return Ok(self.mode);
+        };
+        match (self.mode, self.overwrite) {
+            (WriteMode::Append, _) | (_, OverwritePolicy::Force) => Ok(self.mode),
+            // Like the shell's `noclobber`, only existing regular files are protected, so devices
+            // and pipes (e.g. `/dev/null`) can still be written to
+            (_, OverwritePolicy::Refuse) if is_special_file(path) => Ok(self.mode),
+            (_, OverwritePolicy::Refuse) => Ok(WriteMode::CreateNew),
+            (_, OverwritePolicy::Prompt) => {
+                let path_exists = std::path::Path::new(path).exists();
+                if !path_exists || is_special_file(path) || confirm_overwrite(path)? {
+                    Ok(self.mode)
+                } else {
+                    Err(super::overwrite_refused(path))
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    /// Choose how the output should be compressed (default: [`CompressionMode::Auto`], which
+    /// compresses files ending in `.gz`, `.zst`, or `.xz` and leaves `stdout` uncompressed)
+    ///
+    /// ```no_run
+    /// use std::io::Write;
+    ///
+    /// use clap_stdin::{Compression, CompressionMode, FileOrStdout};
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   output: FileOrStdout,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut writer = args
+    ///     .output
+    ///     .with_compression(CompressionMode::Forced(Compression::Gzip))
+    ///     .into_writer()?;
+    /// writeln!(&mut writer, "compressed")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_compression(mut self, mode: CompressionMode) -> Self {
+        self.compression = mode;
+        self
+    }
+
+    #[cfg(feature = "compression")]
+    /// The codec the output will be compressed with, if any
+    pub(crate) fn codec(&self) -> Option<super::Compression> {
+        match (self.compression, &self.dest) {
+            (CompressionMode::Disabled, _) => None,
+            (CompressionMode::Forced(codec), _) => Some(codec),
+            (CompressionMode::Auto, Dest::Stdout | Dest::Fd { .. }) => None,
+            (CompressionMode::Auto, Dest::Arg(path)) => super::Compression::from_extension(path),
         }
     }
 
     /// Create a writer for the dest, to allow user flexibility of
     /// how to write output (e.g. all at once or in chunks)
     ///
+    /// Call [`OutputWriter::finish`](crate::OutputWriter::finish) when done, to handle any error
+    /// flushing the output (or finalizing a compressed frame). Otherwise this happens when the
+    /// writer is dropped, ignoring errors.
+    ///
     /// ```no_run
     /// use std::io::Write;
     ///
@@ -74,19 +252,147 @@ impl FileOrStdout {
     /// let mut writer = args.output.into_writer()?;
     /// let mut buf = vec![0;8];
     /// writer.write_all(&mut buf)?;
+    /// writer.finish()?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn into_writer(self) -> Result<impl std::io::Write, std::io::Error> {
-        self.dest.into_writer()
+    pub fn into_writer(self) -> Result<OutputWriter, std::io::Error> {
+        #[cfg(feature = "compression")]
+        let codec = self.codec();
+        let mode = self.write_mode()?;
+        let writer = self.dest.into_writer(mode)?;
+        OutputWriter::new(
+            writer,
+            #[cfg(feature = "compression")]
+            codec,
+        )
     }
 
-    #[cfg(feature = "tokio")]
-    /// Create a reader from the source, to allow user flexibility of
-    /// how to read and parse (e.g. all at once or in chunks)
+    #[cfg(feature = "serde")]
+    /// Serialize the value to the dest in the given format, followed by a newline
+    ///
+    /// The output is pretty-printed when writing to a terminal, and compact otherwise
+    /// (YAML is always written in block style).
+    ///
+    /// ```no_run
+    /// use clap::Parser;
+    /// use clap_stdin::{FileOrStdout, Format};
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   output: FileOrStdout,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// args.output.serialize(&vec![1, 2, 3], Format::Json)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn serialize<T: serde::Serialize + ?Sized>(
+        self,
+        value: &T,
+        format: Format,
+    ) -> Result<(), std::io::Error> {
+        use std::io::Write;
+        let output = format.serialize(value, self.is_terminal())?;
+        let mut writer = self.into_writer()?;
+        writer.write_all(output.as_bytes())?;
+        writer.finish()
+    }
+
+    #[cfg(feature = "serde")]
+    /// Is the dest `stdout` attached to a terminal
+    fn is_terminal(&self) -> bool {
+        self.is_stdout() && super::StdinContext::current().stdout_is_terminal()
+    }
+
+    #[cfg(feature = "csv")]
+    /// Create a CSV writer for the dest with the default [`CsvOptions`](crate::CsvOptions)
+    /// (comma-separated, or tab-separated for `.tsv` files, with a header row)
+    ///
+    /// Rows are written with [`csv::Writer::serialize`], and the output is flushed when the writer
+    /// is dropped. To handle any errors, call [`csv::Writer::into_inner`] and
+    /// [`OutputWriter::finish`](crate::OutputWriter::finish).
+    ///
+    /// ```no_run
+    /// use clap_stdin::FileOrStdout;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   output: FileOrStdout,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut writer = args.output.csv_writer()?;
+    /// writer.serialize(("alice", 3))?;
+    /// writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn csv_writer(self) -> Result<csv::Writer<OutputWriter>, std::io::Error> {
+        self.csv_writer_with(crate::CsvOptions::default())
+    }
+
+    #[cfg(feature = "csv")]
+    /// Create a CSV writer for the dest with the given delimiter and header options
+    pub fn csv_writer_with(
+        self,
+        options: crate::CsvOptions,
+    ) -> Result<csv::Writer<OutputWriter>, std::io::Error> {
+        let filename = self.filename().to_owned();
+        Ok(options.writer(&filename, self.into_writer()?))
+    }
+
+    #[cfg(feature = "atomic")]
+    /// Create a writer for the dest that only replaces a destination file once
+    /// [`AtomicWriter::commit`] is called, so a failure part-way through writing
+    /// won't leave a truncated or partially written file behind
+    ///
+    /// Writes to `stdout` are passed through directly.
     ///
     /// ```no_run
     /// use std::io::Write;
+    ///
+    /// use clap_stdin::FileOrStdout;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   output: FileOrStdout,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut writer = args.output.into_atomic_writer()?.with_fsync(true);
+    /// writeln!(&mut writer, "all or nothing")?;
+    /// writer.commit()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_atomic_writer(self) -> Result<AtomicWriter, std::io::Error> {
+        #[cfg(feature = "compression")]
+        let codec = self.codec();
+        let mode = self.write_mode()?;
+        AtomicWriter::new(
+            self.dest,
+            mode,
+            #[cfg(feature = "compression")]
+            codec,
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Create an async writer for the dest, to allow user flexibility of
+    /// how to write output (e.g. all at once or in chunks)
+    ///
+    /// Files are opened the same way as [`FileOrStdout::into_writer`]. Call
+    /// [`shutdown`](tokio::io::AsyncWriteExt::shutdown) when done writing to flush the output
+    /// (and finalize the compressed frame, with compression enabled).
+    ///
+    /// ```no_run
     /// use tokio::io::AsyncWriteExt;
     ///
     /// use clap_stdin::FileOrStdout;
@@ -103,27 +409,163 @@ impl FileOrStdout {
     /// let mut writer = args.output.into_async_writer().await?;
     /// let mut buf = vec![0;8];
     /// writer.write_all(&mut buf).await?;
+    /// writer.shutdown().await?;
     /// # Ok(())
     /// # }
     /// ```
     pub async fn into_async_writer(&self) -> std::io::Result<impl tokio::io::AsyncWrite> {
-        let output: std::pin::Pin<Box<dyn tokio::io::AsyncWrite + 'static>> = match &self.dest {
-            Dest::Stdout => Box::pin(tokio::io::stdout()),
-            Dest::Arg(filepath) => {
-                let f = tokio::fs::File::open(filepath).await?;
-                Box::pin(f)
-            }
-        };
+        let output = self
+            .dest
+            .clone()
+            .into_async_writer(self.write_mode()?)
+            .await?;
+        #[cfg(feature = "compression")]
+        let output = super::compression::encode_async(output, self.codec());
         Ok(output)
     }
+
+    #[cfg(all(feature = "tokio", feature = "serde"))]
+    /// Async version of [`FileOrStdout::serialize`]
+    pub async fn serialize_async<T: serde::Serialize + ?Sized>(
+        &self,
+        value: &T,
+        format: Format,
+    ) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+        let output = format.serialize(value, self.is_terminal())?;
+        let mut writer = std::pin::pin!(self.into_async_writer().await?);
+        writer.write_all(output.as_bytes()).await?;
+        writer.shutdown().await
+    }
+
+    #[cfg(all(feature = "atomic", feature = "tokio"))]
+    /// Async version of [`FileOrStdout::into_atomic_writer`], replacing a destination file once
+    /// [`AsyncAtomicWriter::commit`] is called
+    ///
+    /// ```no_run
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// use clap_stdin::FileOrStdout;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   output: FileOrStdout,
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut writer = args.output.into_async_atomic_writer().await?;
+    /// writer.write_all(b"all or nothing").await?;
+    /// writer.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn into_async_atomic_writer(&self) -> std::io::Result<AsyncAtomicWriter> {
+        AsyncAtomicWriter::new(
+            self.dest.clone(),
+            self.write_mode()?,
+            #[cfg(feature = "compression")]
+            self.codec(),
+        )
+    }
 }
 
 impl FromStr for FileOrStdout {
     type Err = std::io::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let dest = Dest::from_str(s)?;
-        Ok(Self { dest })
+        Ok(Self::from_dest(Dest::from_str(s)?))
+    }
+}
+
+/// What to do when a [`FileOrStdout`] destination file already exists
+///
+/// As with the shell's `noclobber`, this only applies to regular files: devices and pipes
+/// (e.g. `/dev/null` or `<(...)`) are always written to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Overwrite the existing file
+    #[default]
+    Force,
+    /// Fail with [`StdinError::OverwriteRefused`](crate::StdinError::OverwriteRefused)
+    Refuse,
+    /// Ask for confirmation on the terminal, refusing if `stdin` or `stderr` isn't a terminal
+    Prompt,
+}
+
+/// Does the path exist as something other than a regular file (a device, pipe, or directory)
+fn is_special_file(path: &str) -> bool {
+    std::fs::metadata(path).is_ok_and(|metadata| !metadata.is_file())
+}
+
+fn confirm_overwrite(path: &str) -> std::io::Result<bool> {
+    use std::io::IsTerminal;
+
+    let context = super::StdinContext::current();
+    if !context.stdin_is_terminal() || !std::io::stderr().is_terminal() {
+        return Ok(false);
+    }
+    let prompt = format!("overwrite '{path}'? [y/N] ");
+    let answer = super::prompt_line(&prompt, context.stdin_lock(), std::io::stderr())?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "Yes"))
+}
+
+/// [`FileOrStdout`] that appends to the destination file rather than truncating it
+///
+/// ```rust
+/// use std::io::Write;
+/// use clap::Parser;
+/// use clap_stdin::FileOrStdoutAppend;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     log: FileOrStdoutAppend,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// if let Ok(args) = Args::try_parse() {
+///     let mut writer = args.log.into_writer()?;
+///     writeln!(&mut writer, "another line");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileOrStdoutAppend(FileOrStdout);
+
+impl FileOrStdoutAppend {
+    /// Extract the inner [`FileOrStdout`] (configured to append)
+    pub fn into_inner(self) -> FileOrStdout {
+        self.0
+    }
+
+    /// Create a writer for the dest, appending to a destination file
+    pub fn into_writer(self) -> Result<OutputWriter, std::io::Error> {
+        self.0.into_writer()
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Create an async writer for the dest, appending to a destination file
+    pub async fn into_async_writer(&self) -> std::io::Result<impl tokio::io::AsyncWrite> {
+        self.0.into_async_writer().await
+    }
+}
+
+impl FromStr for FileOrStdoutAppend {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FileOrStdout::from_str(s).map(|output| Self(output.with_append(true)))
+    }
+}
+
+impl std::ops::Deref for FileOrStdoutAppend {
+    type Target = FileOrStdout;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
 }
 
@@ -139,3 +581,122 @@ fn test_source_methods() {
     assert!(!val.is_stdout());
     assert_eq!(val.filename(), "/path/to/something");
 }
+
+#[test]
+fn test_append() {
+    let val: FileOrStdout = "/path/to/something".parse().unwrap();
+    assert!(!val.is_append());
+    assert!(val.with_append(true).is_append());
+
+    let val: FileOrStdoutAppend = "/path/to/something".parse().unwrap();
+    assert!(val.is_append());
+    assert_eq!(val.filename(), "/path/to/something");
+}
+
+#[test]
+fn test_overwrite_policy() {
+    use std::io::Write;
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "EXISTING").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let val = FileOrStdout::from_str(path)
+        .unwrap()
+        .with_overwrite(OverwritePolicy::Refuse);
+    let err = val.clone().into_writer().err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(
+        err.to_string(),
+        crate::StdinError::OverwriteRefused(path.to_owned()).to_string()
+    );
+    #[cfg(feature = "atomic")]
+    assert!(val.clone().into_atomic_writer().is_err());
+    assert!(val.with_append(true).into_writer().is_ok());
+    assert_eq!(std::fs::read_to_string(&tmp).unwrap(), "EXISTING");
+
+    let val = FileOrStdout::from_str(path)
+        .unwrap()
+        .with_overwrite(OverwritePolicy::Refuse)
+        .with_force(true);
+    write!(val.into_writer().unwrap(), "FORCED").unwrap();
+    assert_eq!(std::fs::read_to_string(&tmp).unwrap(), "FORCED");
+
+    #[cfg(unix)]
+    {
+        let val = FileOrStdout::from_str("/dev/null")
+            .unwrap()
+            .with_overwrite(OverwritePolicy::Refuse);
+        assert!(val.into_writer().is_ok());
+    }
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_overwrite_prompt() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "EXISTING").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    // Piped input is never taken as the answer
+    let context = crate::StdinContext::new();
+    context.set_stdin("y\n");
+    let _guard = context.enter();
+    let val = FileOrStdout::from_str(path)
+        .unwrap()
+        .with_overwrite(OverwritePolicy::Prompt);
+    let err = val.into_writer().err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(&tmp).unwrap(), "EXISTING");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_serialize() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("output.json");
+
+    let output: FileOrStdout = path.to_str().unwrap().parse().unwrap();
+    output
+        .serialize(&std::collections::BTreeMap::from([("a", 1)]), Format::Json)
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"a\":1}\n");
+
+    let output: FileOrStdout = path.to_str().unwrap().parse().unwrap();
+    let err = output
+        .serialize(
+            &std::collections::HashMap::from([((1, 2), 3)]),
+            Format::Json,
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("unable to serialize"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"a\":1}\n");
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_csv_writer() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("scores.tsv");
+
+    #[derive(serde::Serialize)]
+    struct Row {
+        name: &'static str,
+        score: u32,
+    }
+
+    let output: FileOrStdout = path.to_str().unwrap().parse().unwrap();
+    let mut writer = output.csv_writer().unwrap();
+    writer
+        .serialize(Row {
+            name: "alice",
+            score: 3,
+        })
+        .unwrap();
+    writer.flush().unwrap();
+    drop(writer);
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "name\tscore\nalice\t3\n"
+    );
+}
@@ -0,0 +1,216 @@
+use std::io::{self, Read};
+use std::str::FromStr;
+
+use super::{FileOrStdin, Source, StdinError};
+
+/// Wrapper struct to read zero or more files (or `stdin`) in order, like `cat`
+///
+/// Arguments are parsed by clap as a `Vec<FileOrStdin>`, then collected into `FilesOrStdin`,
+/// which reads from `stdin` when no files are given and ensures `-` is passed at most once.
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::{FileOrStdin, FilesOrStdin};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     inputs: Vec<FileOrStdin>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// if let Ok(args) = Args::try_parse() {
+///     let inputs = FilesOrStdin::new(args.inputs)?;
+///     println!("inputs={}", inputs.contents()?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```sh
+/// $ echo "1 2" > first.txt
+/// $ echo "3 4" | ./example first.txt -
+/// 1 2
+/// 3 4
+/// ```
+#[derive(Debug, Clone)]
+pub struct FilesOrStdin<T = String> {
+    pub(crate) inputs: Vec<FileOrStdin<T>>,
+}
+
+impl<T> FilesOrStdin<T> {
+    /// Collect the inputs, reading from `stdin` if there are none
+    ///
+    /// Returns [`StdinError::StdInRepeatedUse`] if `stdin` is given more than once.
+    pub fn new(inputs: impl IntoIterator<Item = FileOrStdin<T>>) -> Result<Self, StdinError> {
+        let mut inputs: Vec<_> = inputs.into_iter().collect();
+        if inputs.iter().filter(|input| input.is_stdin()).count() > 1 {
+            return Err(StdinError::StdInRepeatedUse);
+        }
+        if inputs.is_empty() {
+            inputs.push(FileOrStdin::from_source(Source::Stdin));
+        }
+        Ok(Self { inputs })
+    }
+
+    /// Is `stdin` one of the inputs
+    pub fn is_stdin(&self) -> bool {
+        self.inputs.iter().any(|input| input.is_stdin())
+    }
+
+    /// The values passed for each input, in order (Either "-" for stdin or a filepath)
+    pub fn filenames(&self) -> impl Iterator<Item = &str> {
+        self.inputs.iter().map(|input| input.filename())
+    }
+
+    /// Iterate over the individual inputs, to read each file separately
+    pub fn iter(&self) -> std::slice::Iter<'_, FileOrStdin<T>> {
+        self.inputs.iter()
+    }
+
+    /// Read the contents of all inputs in order, returning T::from_str on the concatenation
+    pub fn contents(self) -> Result<T, StdinError>
+    where
+        T: FromStr + 'static,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let mut reader = self.into_reader()?;
+        let mut input = String::new();
+        let _ = reader.read_to_string(&mut input)?;
+        T::from_str(input.trim_end()).map_err(|e| StdinError::FromStr(format!("{e}")))
+    }
+
+    /// Create a single reader that reads through each input in order
+    ///
+    /// Each file is opened once the previous input has been read to the end,
+    /// and errors opening a file include the filename.
+    ///
+    /// ```no_run
+    /// use std::io::{BufRead, BufReader};
+    ///
+    /// use clap_stdin::{FileOrStdin, FilesOrStdin};
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///   inputs: Vec<FileOrStdin>,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let reader = FilesOrStdin::new(args.inputs)?.into_reader()?;
+    /// for line in BufReader::new(reader).lines() {
+    ///     println!("{}", line?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_reader(self) -> Result<impl Read, StdinError>
+    where
+        T: 'static,
+    {
+        Ok(ChainedReader {
+            inputs: self.inputs.into_iter(),
+            current: None,
+        })
+    }
+}
+
+impl<T> IntoIterator for FilesOrStdin<T> {
+    type Item = FileOrStdin<T>;
+    type IntoIter = std::vec::IntoIter<FileOrStdin<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inputs.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a FilesOrStdin<T> {
+    type Item = &'a FileOrStdin<T>;
+    type IntoIter = std::slice::Iter<'a, FileOrStdin<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inputs.iter()
+    }
+}
+
+impl<T> TryFrom<Vec<FileOrStdin<T>>> for FilesOrStdin<T> {
+    type Error = StdinError;
+
+    fn try_from(inputs: Vec<FileOrStdin<T>>) -> Result<Self, Self::Error> {
+        Self::new(inputs)
+    }
+}
+
+/// Reader that opens each input lazily, moving on to the next at EOF
+struct ChainedReader<T> {
+    inputs: std::vec::IntoIter<FileOrStdin<T>>,
+    current: Option<Box<dyn Read>>,
+}
+
+impl<T: 'static> Read for ChainedReader<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let reader = match self.current.as_mut() {
+                Some(reader) => reader,
+                None => {
+                    let Some(input) = self.inputs.next() else {
+                        return Ok(0);
+                    };
+                    let filename = input.filename().to_owned();
+                    let reader = input.into_reader().map_err(|e| match e {
+                        StdinError::StdIn(e) => {
+                            io::Error::new(e.kind(), format!("{filename}: {e}"))
+                        }
+                        e => io::Error::other(e),
+                    })?;
+                    self.current.insert(Box::new(reader))
+                }
+            };
+            match reader.read(buf)? {
+                0 if !buf.is_empty() => self.current = None,
+                n => return Ok(n),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_files_or_stdin() {
+    let first = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&first, "FIRST\n").unwrap();
+    let second = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&second, "SECOND\n").unwrap();
+    let paths = [
+        first.path().to_str().unwrap(),
+        second.path().to_str().unwrap(),
+    ];
+
+    let inputs: Vec<FileOrStdin> = paths.iter().map(|p| p.parse().unwrap()).collect();
+    let inputs = FilesOrStdin::new(inputs).unwrap();
+    assert!(!inputs.is_stdin());
+    assert_eq!(inputs.filenames().collect::<Vec<_>>(), paths);
+    assert_eq!(inputs.contents().unwrap(), "FIRST\nSECOND");
+
+    let inputs = FilesOrStdin::<String>::new(vec![]).unwrap();
+    assert!(inputs.is_stdin());
+    assert_eq!(inputs.filenames().collect::<Vec<_>>(), ["-"]);
+
+    let inputs: Vec<FileOrStdin> = ["-", paths[0], "-"]
+        .iter()
+        .map(|p| p.parse().unwrap())
+        .collect();
+    assert!(matches!(
+        FilesOrStdin::new(inputs),
+        Err(StdinError::StdInRepeatedUse)
+    ));
+}
+
+#[test]
+fn test_files_or_stdin_sentinels() {
+    let context = crate::StdinContext::new();
+    context.set_stdin_sentinels(["stdin"]);
+    let _guard = context.enter();
+
+    // No inputs still reads from stdin, even when `-` isn't a sentinel
+    let inputs = FilesOrStdin::<String>::new(vec![]).unwrap();
+    assert!(inputs.is_stdin());
+}
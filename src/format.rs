@@ -0,0 +1,266 @@
+use std::io::Read;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+#[cfg(any(feature = "json", feature = "yaml", feature = "toml"))]
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::StdinError;
+
+/// Serialization formats that can be deserialized from an input, each enabled by the cargo
+/// feature of the same name (`json`, `yaml`, `toml`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl Format {
+    /// Detect the format from a file extension (`.json`, `.yaml`/`.yml`, `.toml`)
+    ///
+    /// A trailing compression extension (e.g. `.json.gz`) is skipped when the
+    /// codec's feature is enabled.
+    pub fn from_extension(path: impl AsRef<Path>) -> Option<Self> {
+        match super::content_extension(path.as_ref())? {
+            #[cfg(feature = "json")]
+            "json" => Some(Self::Json),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(Self::Yaml),
+            #[cfg(feature = "toml")]
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Deserialize a value in this format from the reader
+    pub fn deserialize<T: DeserializeOwned>(self, reader: impl Read) -> Result<T, StdinError> {
+        #[cfg(not(any(feature = "json", feature = "yaml", feature = "toml")))]
+        let _ = reader;
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::from_reader(reader).map_err(deserialize_error),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_norway::from_reader(reader).map_err(deserialize_error),
+            #[cfg(feature = "toml")]
+            Self::Toml => {
+                let mut reader = reader;
+                let mut input = String::new();
+                let _ = reader.read_to_string(&mut input)?;
+                toml::from_str(&input).map_err(deserialize_error)
+            }
+        }
+    }
+}
+
+impl Format {
+    /// Serialize the value in this format, always ending with a newline
+    pub(crate) fn serialize<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+        pretty: bool,
+    ) -> std::io::Result<String> {
+        let mut output = self.serialize_raw(value, pretty)?;
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    /// Serialize the value as output by the format's serializer
+    fn serialize_raw<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+        pretty: bool,
+    ) -> std::io::Result<String> {
+        #[cfg(not(any(feature = "json", feature = "yaml", feature = "toml")))]
+        let _ = value;
+        #[cfg(not(any(feature = "json", feature = "toml")))]
+        let _ = pretty;
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json if pretty => serde_json::to_string_pretty(value).map_err(serialize_error),
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::to_string(value).map_err(serialize_error),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_norway::to_string(value).map_err(serialize_error),
+            #[cfg(feature = "toml")]
+            Self::Toml if pretty => toml::to_string_pretty(value).map_err(serialize_error),
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::to_string(value).map_err(serialize_error),
+        }
+    }
+}
+
+#[cfg(any(feature = "json", feature = "yaml", feature = "toml"))]
+fn serialize_error(e: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        StdinError::Serialize(format!("{e}")),
+    )
+}
+
+#[cfg(any(feature = "json", feature = "yaml", feature = "toml"))]
+fn deserialize_error(e: impl std::fmt::Display) -> StdinError {
+    StdinError::Deserialize(format!("{e}"))
+}
+
+/// Types that can be read from an input with [`FileOrStdin::deserialize`](crate::FileOrStdin::deserialize)
+///
+/// The `format` is the one chosen with [`FileOrStdin::with_format`](crate::FileOrStdin::with_format),
+/// or detected from the file extension of the input. Only [`Auto`] makes use of it, the other
+/// wrappers always use their own format.
+pub trait FromReader: Sized {
+    /// Deserialize the value from the reader
+    fn from_reader(reader: impl Read, format: Option<Format>) -> Result<Self, StdinError>;
+}
+
+macro_rules! format_wrapper {
+    ($feature:literal, $name:ident, $doc:literal) => {
+        #[cfg(feature = $feature)]
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name<T>(pub T);
+
+        #[cfg(feature = $feature)]
+        impl<T> $name<T> {
+            /// Unwrap the deserialized value
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T: DeserializeOwned> FromReader for $name<T> {
+            fn from_reader(reader: impl Read, _: Option<Format>) -> Result<Self, StdinError> {
+                Format::$name.deserialize(reader).map(Self)
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T: DeserializeOwned> FromStr for $name<T> {
+            type Err = StdinError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Format::$name.deserialize(s.as_bytes()).map(Self)
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T> Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T> DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+    };
+}
+
+format_wrapper!(
+    "json",
+    Json,
+    "Wrapper to deserialize `T` from JSON, e.g. `FileOrStdin<Json<T>>`"
+);
+format_wrapper!(
+    "yaml",
+    Yaml,
+    "Wrapper to deserialize `T` from YAML, e.g. `FileOrStdin<Yaml<T>>`"
+);
+format_wrapper!(
+    "toml",
+    Toml,
+    "Wrapper to deserialize `T` from TOML, e.g. `FileOrStdin<Toml<T>>`"
+);
+
+/// Wrapper to deserialize `T` in the format detected from the file extension, e.g. `FileOrStdin<Auto<T>>`
+///
+/// Since `stdin` has no file extension, the format must be chosen with
+/// [`FileOrStdin::with_format`](crate::FileOrStdin::with_format) when reading from `stdin`.
+///
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{Auto, FileOrStdin, Format};
+/// use serde::Deserialize;
+///
+/// #[derive(Clone, Debug, Deserialize)]
+/// struct Config {
+///     name: String,
+/// }
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     config: FileOrStdin<Auto<Config>>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let config = args.config.with_format(Format::Json).deserialize()?;
+/// println!("name={}", config.name);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Auto<T>(pub T);
+
+impl<T> Auto<T> {
+    /// Unwrap the deserialized value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromReader for Auto<T> {
+    fn from_reader(reader: impl Read, format: Option<Format>) -> Result<Self, StdinError> {
+        let format = format.ok_or(StdinError::UnknownFormat)?;
+        format.deserialize(reader).map(Self)
+    }
+}
+
+impl<T> Deref for Auto<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Auto<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_format_from_extension() {
+    assert_eq!(Format::from_extension("config.json"), Some(Format::Json));
+    assert_eq!(Format::from_extension("config"), None);
+    assert_eq!(Format::from_extension("-"), None);
+    #[cfg(feature = "gzip")]
+    assert_eq!(Format::from_extension("config.json.gz"), Some(Format::Json));
+    #[cfg(feature = "yaml")]
+    assert_eq!(Format::from_extension("config.yml"), Some(Format::Yaml));
+    #[cfg(feature = "toml")]
+    assert_eq!(Format::from_extension("Cargo.toml"), Some(Format::Toml));
+
+    let value: Json<Vec<u32>> = "[1, 2, 3]".parse().unwrap();
+    assert_eq!(*value, [1, 2, 3]);
+    assert!(matches!(
+        Auto::<u32>::from_reader("1".as_bytes(), None),
+        Err(StdinError::UnknownFormat)
+    ));
+}
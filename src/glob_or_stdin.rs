@@ -0,0 +1,178 @@
+use std::str::FromStr;
+
+use super::{FileOrStdin, FilesOrStdin, Source, StdinError};
+
+/// Wrapper struct to read files matching a glob pattern, or contents from `stdin`
+///
+/// Patterns are expanded when the arg is parsed, for cases where the shell doesn't expand them
+/// (quoted args, config files, CI runners). Matching files are sorted (directories are skipped),
+/// and a pattern matching no files fails with [`StdinError::NoGlobMatches`]. Values without glob characters (`*?[`) are
+/// used as a filepath as-is, and `-` is read from `stdin`.
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::{FilesOrStdin, GlobOrStdin};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     inputs: Vec<GlobOrStdin>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// if let Ok(args) = Args::try_parse() {
+///     let inputs = FilesOrStdin::new(args.inputs.into_iter().flatten())?;
+///     println!("inputs={}", inputs.contents()?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```sh
+/// $ ./example 'data/*.json'
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlobOrStdin<T = String> {
+    pattern: String,
+    files: Vec<FileOrStdin<T>>,
+}
+
+impl<T> GlobOrStdin<T> {
+    /// Was this value read from stdin
+    pub fn is_stdin(&self) -> bool {
+        self.files.iter().any(|file| file.is_stdin())
+    }
+
+    /// The value passed to this arg (Either "-" for stdin or a glob pattern)
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The sorted filepaths matching the pattern (or "-" for stdin)
+    pub fn filenames(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|file| file.filename())
+    }
+
+    /// Iterate over the matching files, to read each file separately
+    pub fn iter(&self) -> std::slice::Iter<'_, FileOrStdin<T>> {
+        self.files.iter()
+    }
+
+    /// Read the contents of all matching files in order, returning T::from_str on the concatenation
+    pub fn contents(self) -> Result<T, StdinError>
+    where
+        T: FromStr + 'static,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        FilesOrStdin::from(self).contents()
+    }
+
+    /// Create a single reader that reads through each matching file in order
+    pub fn into_reader(self) -> Result<impl std::io::Read, StdinError>
+    where
+        T: 'static,
+    {
+        FilesOrStdin::from(self).into_reader()
+    }
+}
+
+impl<T> FromStr for GlobOrStdin<T> {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pattern = s.to_owned();
+        if !s.contains(['*', '?', '[']) || super::StdinContext::current().is_stdin_sentinel(s) {
+            return Ok(Self {
+                files: vec![FileOrStdin::from_str(s)?],
+                pattern,
+            });
+        }
+        let paths =
+            glob::glob(s).map_err(|e| StdinError::GlobPattern(pattern.clone(), e.to_string()))?;
+        let mut paths = paths
+            .map(|path| path.map_err(std::io::Error::from))
+            .filter(|path| path.as_ref().map_or(true, |path| !path.is_dir()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if paths.is_empty() {
+            return Err(StdinError::NoGlobMatches(pattern));
+        }
+        paths.sort();
+        // Matches are always files, even when named like `-` or `fd:3`
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let path = path.into_os_string().into_string().map_err(|path| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("matched a path that isn't valid unicode: {path:?}"),
+                    )
+                })?;
+                Ok(FileOrStdin::from_source(Source::Arg(path)))
+            })
+            .collect::<Result<_, StdinError>>()?;
+        Ok(Self { pattern, files })
+    }
+}
+
+impl<T> IntoIterator for GlobOrStdin<T> {
+    type Item = FileOrStdin<T>;
+    type IntoIter = std::vec::IntoIter<FileOrStdin<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.files.into_iter()
+    }
+}
+
+impl<T> From<GlobOrStdin<T>> for FilesOrStdin<T> {
+    fn from(glob: GlobOrStdin<T>) -> Self {
+        // A single arg matches `stdin` at most once
+        Self { inputs: glob.files }
+    }
+}
+
+#[test]
+fn test_glob_or_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["b.json", "a.json", "c.txt"] {
+        std::fs::write(dir.path().join(name), name).unwrap();
+    }
+    std::fs::create_dir(dir.path().join("d.json")).unwrap();
+    let pattern = format!("{}/*.json", dir.path().display());
+
+    let val: GlobOrStdin = pattern.parse().unwrap();
+    assert!(!val.is_stdin());
+    assert_eq!(val.pattern(), pattern);
+    let expected: Vec<_> = ["a.json", "b.json"]
+        .iter()
+        .map(|name| dir.path().join(name).display().to_string())
+        .collect();
+    assert_eq!(val.filenames().collect::<Vec<_>>(), expected);
+    assert_eq!(val.contents().unwrap(), "a.jsonb.json");
+
+    let val: GlobOrStdin = "-".parse().unwrap();
+    assert!(val.is_stdin());
+
+    let val: GlobOrStdin = "/path/to/something".parse().unwrap();
+    assert_eq!(val.filenames().collect::<Vec<_>>(), ["/path/to/something"]);
+
+    let pattern = format!("{}/d.*", dir.path().display());
+    assert!(matches!(
+        pattern.parse::<GlobOrStdin>(),
+        Err(StdinError::NoGlobMatches(p)) if p == pattern
+    ));
+
+    let pattern = format!("{}/*.csv", dir.path().display());
+    assert!(matches!(
+        pattern.parse::<GlobOrStdin>(),
+        Err(StdinError::NoGlobMatches(p)) if p == pattern
+    ));
+    assert!(matches!(
+        "data/[*.json".parse::<GlobOrStdin>(),
+        Err(StdinError::GlobPattern(..))
+    ));
+
+    #[cfg(unix)]
+    {
+        let val: GlobOrStdin = "/dev/fd/[0]".parse().unwrap();
+        assert!(!val.is_stdin());
+        assert_eq!(val.filenames().collect::<Vec<_>>(), ["/dev/fd/0"]);
+    }
+}
@@ -0,0 +1,238 @@
+use std::io::{self, BufRead, Read};
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use super::StdinError;
+
+/// ASCII record separator, which prefixes each value of a JSON text sequence (RFC 7464)
+const RECORD_SEPARATOR: u8 = 0x1e;
+
+fn record_error(record: usize, e: impl std::fmt::Display) -> StdinError {
+    StdinError::DeserializeRecord {
+        record,
+        error: format!("{e}"),
+    }
+}
+
+/// Iterator deserializing each whitespace-separated JSON value of a reader (NDJSON or a JSON text sequence)
+pub(crate) struct Values<R: Read, U> {
+    inner: serde_json::StreamDeserializer<'static, serde_json::de::IoRead<SkipSeparators<R>>, U>,
+    record: usize,
+    done: bool,
+}
+
+impl<R: Read, U: DeserializeOwned> Values<R, U> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            inner: serde_json::Deserializer::from_reader(SkipSeparators(reader)).into_iter(),
+            record: 0,
+            done: false,
+        }
+    }
+}
+
+impl<R: Read, U: DeserializeOwned> Iterator for Values<R, U> {
+    type Item = Result<U, StdinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        self.record += 1;
+        match self.inner.next()? {
+            Ok(value) => Some(Ok(value)),
+            Err(e) => {
+                // The stream can't be resynchronized after a syntax error
+                self.done = true;
+                Some(Err(match e.io_error_kind() {
+                    Some(_) => io::Error::from(e).into(),
+                    None => record_error(self.record, e),
+                }))
+            }
+        }
+    }
+}
+
+/// Reader that replaces record separators with whitespace, which can't appear unescaped within JSON
+struct SkipSeparators<R>(R);
+
+impl<R: Read> Read for SkipSeparators<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.0.read(buf)?;
+        for b in &mut buf[..n] {
+            if *b == RECORD_SEPARATOR {
+                *b = b'\n';
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// Iterator deserializing each element of a top-level JSON array, buffering one element at a time
+pub(crate) struct Elements<R, U> {
+    reader: R,
+    state: State,
+    record: usize,
+    _type: PhantomData<U>,
+}
+
+#[derive(PartialEq)]
+enum State {
+    Start,
+    Elements,
+    Done,
+}
+
+impl<R: BufRead, U: DeserializeOwned> Elements<R, U> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            state: State::Start,
+            record: 0,
+            _type: PhantomData,
+        }
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.reader.fill_buf()?.first().copied();
+        if byte.is_some() {
+            self.reader.consume(1);
+        }
+        Ok(byte)
+    }
+
+    fn next_non_whitespace(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            match self.next_byte()? {
+                Some(b) if b.is_ascii_whitespace() => continue,
+                byte => return Ok(byte),
+            }
+        }
+    }
+
+    /// Read the bytes of the next element, up to (and consuming) the following `,` or `]`
+    fn next_element(&mut self) -> Result<Vec<u8>, StdinError> {
+        let mut element = Vec::new();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let Some(b) = self.next_byte()? else {
+                return Err(record_error(self.record, "unexpected end of input"));
+            };
+            if in_string {
+                match b {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+            } else {
+                match b {
+                    b'"' => in_string = true,
+                    b'[' | b'{' => depth += 1,
+                    b']' if depth == 0 => {
+                        self.state = State::Done;
+                        return Ok(element);
+                    }
+                    b',' if depth == 0 => return Ok(element),
+                    b']' | b'}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            element.push(b);
+        }
+    }
+}
+
+impl<R: BufRead, U: DeserializeOwned> Iterator for Elements<R, U> {
+    type Item = Result<U, StdinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Start {
+            self.state = State::Done;
+            match self.next_non_whitespace() {
+                Ok(Some(b'[')) => self.state = State::Elements,
+                Ok(_) => {
+                    return Some(Err(StdinError::Deserialize(
+                        "expected a top-level JSON array".into(),
+                    )));
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        if self.state == State::Done {
+            return None;
+        }
+        self.record += 1;
+        let element = match self.next_element() {
+            Ok(element) => element,
+            Err(e) => {
+                self.state = State::Done;
+                return Some(Err(e));
+            }
+        };
+        if self.state == State::Done && self.record == 1 && element.trim_ascii().is_empty() {
+            // An empty array
+            return None;
+        }
+        let result = serde_json::from_slice(&element).map_err(|e| record_error(self.record, e));
+        if result.is_err() {
+            self.state = State::Done;
+        }
+        Some(result)
+    }
+}
+
+#[test]
+fn test_json_stream() {
+    let input = "{\"a\": 1}\n\n{\"a\": 2}\n\x1e{\"a\": 3}\n";
+    let values: Vec<std::collections::BTreeMap<String, u32>> = Values::new(input.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[2]["a"], 3);
+
+    let mut values = Values::<_, u32>::new("1\n2\nthree\n4".as_bytes());
+    assert_eq!(values.next().unwrap().unwrap(), 1);
+    assert_eq!(values.next().unwrap().unwrap(), 2);
+    assert!(matches!(
+        values.next(),
+        Some(Err(StdinError::DeserializeRecord { record: 3, .. }))
+    ));
+    assert!(values.next().is_none());
+
+    let input = r#" [ {"a": [1, "],"]}, {"a": [2]} ,{"a":[]}, {"a": [3, "\"]"]} ] "#;
+    let elements: Vec<std::collections::BTreeMap<String, serde_json::Value>> =
+        Elements::new(input.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+    assert_eq!(elements.len(), 4);
+    assert_eq!(elements[3]["a"][1], "\"]");
+
+    for empty in ["[]", " [ ] "] {
+        assert_eq!(Elements::<_, u32>::new(empty.as_bytes()).count(), 0);
+    }
+
+    let mut elements = Elements::<_, u32>::new("[1, 2, \"three\"]".as_bytes());
+    assert_eq!(elements.next().unwrap().unwrap(), 1);
+    assert_eq!(elements.next().unwrap().unwrap(), 2);
+    assert!(matches!(
+        elements.next(),
+        Some(Err(StdinError::DeserializeRecord { record: 3, .. }))
+    ));
+    assert!(elements.next().is_none());
+    assert!(
+        Elements::<_, u32>::new("[1, 2".as_bytes())
+            .last()
+            .unwrap()
+            .is_err()
+    );
+    assert!(
+        Elements::<_, u32>::new("{}".as_bytes())
+            .next()
+            .unwrap()
+            .is_err()
+    );
+}
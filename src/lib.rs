@@ -2,31 +2,119 @@
 
 use std::io::{self, Read};
 use std::str::FromStr;
-use std::sync::atomic::AtomicBool;
 
 mod maybe_stdin;
 pub use maybe_stdin::MaybeStdin;
+mod value_or_file;
+pub use value_or_file::ValueOrFile;
+mod fallback;
+pub use fallback::{Fallback, Origin, Resolved};
 mod file_or_stdin;
 pub use file_or_stdin::FileOrStdin;
+mod files_or_stdin;
+pub use files_or_stdin::FilesOrStdin;
+#[cfg(feature = "glob")]
+mod glob_or_stdin;
+#[cfg(feature = "glob")]
+pub use glob_or_stdin::GlobOrStdin;
 mod file_or_stdout;
-pub use file_or_stdout::FileOrStdout;
-
-static STDIN_HAS_BEEN_READ: AtomicBool = AtomicBool::new(false);
+pub use file_or_stdout::{FileOrStdout, FileOrStdoutAppend, OverwritePolicy};
+#[cfg(feature = "atomic")]
+mod atomic_writer;
+mod output_writer;
+pub use output_writer::OutputWriter;
+mod context;
+pub use context::{
+    StdinContext, StdinContextGuard, TerminalPolicy, set_max_stdin_size, set_stdin_sentinels,
+    set_stdout_sentinels, set_terminal_policy,
+};
+mod limits;
+#[cfg(all(feature = "atomic", feature = "tokio"))]
+pub use atomic_writer::AsyncAtomicWriter;
+#[cfg(feature = "atomic")]
+pub use atomic_writer::AtomicWriter;
+#[cfg(feature = "compression")]
+mod compression;
+#[cfg(feature = "compression")]
+pub use compression::{Compression, CompressionMode};
+#[cfg(feature = "serde")]
+mod format;
+#[cfg(feature = "json")]
+pub use format::Json;
+#[cfg(feature = "toml")]
+pub use format::Toml;
+#[cfg(feature = "yaml")]
+pub use format::Yaml;
+#[cfg(feature = "json")]
+mod json_stream;
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "mmap")]
+pub use mapped::Mapped;
+#[cfg(feature = "csv")]
+mod tabular;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "serde")]
+pub use format::{Auto, Format, FromReader};
+#[cfg(feature = "csv")]
+pub use tabular::CsvOptions;
 
 #[derive(Debug, thiserror::Error)]
 pub enum StdinError {
     #[error("stdin read from more than once")]
     StdInRepeatedUse,
+    #[error("fd {0} used more than once")]
+    FdRepeatedUse(u32),
     #[error(transparent)]
-    StdIn(#[from] io::Error),
+    StdIn(io::Error),
+    #[error("stdin is a terminal; pipe data or pass a file")]
+    StdinIsTerminal,
+    #[error("no value provided; tried {0}")]
+    MissingValue(String),
     #[error("unable to parse from_str: {0}")]
     FromStr(String),
+    #[error("line {line}: unable to parse from_str: {error}")]
+    FromStrLine { line: usize, error: String },
+    #[error("unable to convert from bytes: {0}")]
+    FromBytes(String),
+    #[error("input exceeds the maximum size of {limit} bytes")]
+    SizeLimit { limit: u64 },
+    #[error("line {line}: exceeds the maximum length of {limit} bytes")]
+    LineTooLong { line: usize, limit: usize },
+    #[error("refusing to overwrite existing file: {0}")]
+    OverwriteRefused(String),
+    #[error("invalid glob pattern '{0}': {1}")]
+    GlobPattern(String, String),
+    #[error("no files match glob pattern: {0}")]
+    NoGlobMatches(String),
+    #[error("unable to deserialize: {0}")]
+    Deserialize(String),
+    #[error("record {record}: unable to deserialize: {error}")]
+    DeserializeRecord { record: usize, error: String },
+    #[error("unable to detect the input format from its file extension")]
+    UnknownFormat,
+    #[error("unable to serialize: {0}")]
+    Serialize(String),
+}
+
+impl From<io::Error> for StdinError {
+    /// Unwraps a `StdinError` that was reported through an `io::Error` (e.g. by a reader)
+    fn from(e: io::Error) -> Self {
+        if e.get_ref().is_some_and(|inner| inner.is::<StdinError>()) {
+            let inner = e.into_inner().expect("checked for an inner error");
+            return *inner.downcast().expect("checked for a StdinError");
+        }
+        Self::StdIn(e)
+    }
 }
 
-/// Source of the value contents will be either from `stdin` or a CLI arg provided value
+/// Source of the value contents will be either from `stdin`, an inherited file descriptor,
+/// or a CLI arg provided value
 #[derive(Clone)]
 pub(crate) enum Source {
     Stdin,
+    Fd { fd: u32, arg: String },
     Arg(String),
 }
 
@@ -34,12 +122,11 @@ impl Source {
     pub(crate) fn into_reader(self) -> Result<impl std::io::Read, StdinError> {
         let input: Box<dyn std::io::Read + 'static> = match self {
             Source::Stdin => {
-                if STDIN_HAS_BEEN_READ.load(std::sync::atomic::Ordering::Acquire) {
-                    return Err(StdinError::StdInRepeatedUse);
-                }
-                STDIN_HAS_BEEN_READ.store(true, std::sync::atomic::Ordering::SeqCst);
-                Box::new(std::io::stdin())
+                let context = StdinContext::current();
+                context.claim()?;
+                context.stdin()
             }
+            Source::Fd { fd, .. } => Box::new(open_fd(fd)?),
             Source::Arg(filepath) => {
                 let f = std::fs::File::open(filepath)?;
                 Box::new(f)
@@ -48,31 +135,148 @@ impl Source {
         Ok(input)
     }
 
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn into_async_reader(
+        self,
+    ) -> Result<std::pin::Pin<Box<dyn tokio::io::AsyncRead>>, StdinError> {
+        match self {
+            Source::Stdin => {
+                let context = StdinContext::current();
+                context.claim()?;
+                Ok(context.async_stdin())
+            }
+            Source::Fd { fd, .. } => Ok(Box::pin(tokio::fs::File::from_std(open_fd(fd)?))),
+            Source::Arg(filepath) => {
+                let f = tokio::fs::File::open(filepath).await?;
+                Ok(Box::pin(f))
+            }
+        }
+    }
+
+    /// Parse a file arg, which refers to `stdin` when it is a sentinel
+    pub(crate) fn parse(s: &str, is_sentinel: bool) -> Self {
+        if is_sentinel {
+            return Self::Stdin;
+        }
+        match parse_fd(s) {
+            Some(0) => Self::Stdin,
+            Some(fd) => Self::Fd {
+                fd,
+                arg: s.to_owned(),
+            },
+            None => Self::Arg(s.to_owned()),
+        }
+    }
+
+    /// Parse the arg of a literal value, which is only read from `stdin` when it is a sentinel
+    ///
+    /// Unlike [`Source::parse`], file descriptor args (`fd:N`) are kept as literal values.
+    pub(crate) fn from_value(s: &str, is_sentinel: bool) -> Self {
+        if is_sentinel {
+            return Self::Stdin;
+        }
+        Self::Arg(s.to_owned())
+    }
+
+    /// The value read from `stdin`, or the literal value of the arg
     pub(crate) fn get_value(self) -> Result<String, StdinError> {
+        match StdinContext::current().terminal_policy() {
+            TerminalPolicy::Prompt(prompt) => self.get_value_with_prompt(Some(prompt)),
+            _ => self.get_value_with_prompt(None),
+        }
+    }
+
+    /// The value read from `stdin` (prompting for a line when given a prompt and `stdin` is a
+    /// terminal), or the literal value of the arg
+    pub(crate) fn get_value_with_prompt(self, prompt: Option<&str>) -> Result<String, StdinError> {
         match self {
             Source::Stdin => {
-                if STDIN_HAS_BEEN_READ.load(std::sync::atomic::Ordering::Acquire) {
-                    return Err(StdinError::StdInRepeatedUse);
+                let context = StdinContext::current();
+                if let Some(prompt) = prompt
+                    && context.stdin_is_terminal()
+                {
+                    return Ok(prompt_line(prompt, context.stdin_lock(), io::stderr())?);
                 }
-                STDIN_HAS_BEEN_READ.store(true, std::sync::atomic::Ordering::SeqCst);
-                let stdin = io::stdin();
+                context.claim()?;
+                let limits = limits::Limits {
+                    max_size: context.max_size(),
+                    ..Default::default()
+                };
                 let mut input = String::new();
-                stdin.lock().read_to_string(&mut input)?;
+                limits::Limited::new(context.stdin_lock(), limits).read_to_string(&mut input)?;
                 Ok(input)
             }
-            Source::Arg(value) => Ok(value),
+            Source::Fd { arg, .. } | Source::Arg(arg) => Ok(arg),
+        }
+    }
+}
+
+/// Parse an inherited file descriptor arg (`fd:N` or `/dev/fd/N`), only supported on unix
+fn parse_fd(s: &str) -> Option<u32> {
+    if !cfg!(unix) {
+        return None;
+    }
+    s.strip_prefix("fd:")
+        .or_else(|| s.strip_prefix("/dev/fd/"))?
+        .parse()
+        .ok()
+}
+
+/// Duplicate an inherited file descriptor, which (like `stdin`) can only be used once
+///
+/// The descriptor is duplicated rather than reopened, so its offset and flags (e.g. `O_APPEND` from
+/// `3>>file`) are kept as the parent process set them up.
+pub(crate) fn open_fd(fd: u32) -> io::Result<std::fs::File> {
+    #[cfg(unix)]
+    {
+        use std::os::fd::{FromRawFd, OwnedFd};
+
+        let raw = i32::try_from(fd)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid file descriptor"))?;
+        // SAFETY: F_DUPFD_CLOEXEC only takes the descriptor number, failing with EBADF if it
+        // isn't open in this process
+        let dup = unsafe { libc::fcntl(raw, libc::F_DUPFD_CLOEXEC, 0) };
+        if dup < 0 {
+            return Err(io::Error::last_os_error());
         }
+        // SAFETY: fcntl returned a new descriptor, which nothing else owns
+        let file = std::fs::File::from(unsafe { OwnedFd::from_raw_fd(dup) });
+        // Only claimed once it's known to be open, so a failed attempt can be retried
+        StdinContext::current()
+            .claim_fd(fd)
+            .map_err(io::Error::other)?;
+        Ok(file)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = fd;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "file descriptor args are only supported on unix",
+        ))
     }
 }
 
+/// Write the prompt and read a single line of input, without the trailing newline
+fn prompt_line(
+    prompt: &str,
+    mut input: impl io::BufRead,
+    mut output: impl io::Write,
+) -> io::Result<String> {
+    write!(output, "{prompt}")?;
+    output.flush()?;
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Ok(line)
+}
+
 impl FromStr for Source {
     type Err = StdinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "-" => Ok(Self::Stdin),
-            arg => Ok(Self::Arg(arg.to_owned())),
-        }
+        Ok(Self::parse(s, StdinContext::current().is_stdin_sentinel(s)))
     }
 }
 
@@ -80,43 +284,127 @@ impl std::fmt::Debug for Source {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Source::Stdin => write!(f, "stdin"),
-            Source::Arg(v) => v.fmt(f),
+            Source::Fd { arg, .. } | Source::Arg(arg) => arg.fmt(f),
         }
     }
 }
 
-/// Destination of the value contents will be either `stdout` or a CLI arg provided filepath
+/// Destination of the value contents will be either `stdout`, an inherited file descriptor,
+/// or a CLI arg provided filepath
 #[derive(Clone)]
 pub(crate) enum Dest {
     Stdout,
+    Fd { fd: u32, arg: String },
     Arg(String),
 }
 
+/// How a destination file is opened for writing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum WriteMode {
+    /// Create the file, or truncate the existing contents
+    #[default]
+    Truncate,
+    /// Create the file, or append to the existing contents
+    Append,
+    /// Create the file, failing if it already exists
+    CreateNew,
+}
+
+impl WriteMode {
+    /// Report an existing file as [`StdinError::OverwriteRefused`] when it was required to be new
+    pub(crate) fn open_error(self, e: io::Error, path: &str) -> io::Error {
+        match e.kind() {
+            io::ErrorKind::AlreadyExists if self == WriteMode::CreateNew => overwrite_refused(path),
+            _ => e,
+        }
+    }
+}
+
+#[cfg(any(feature = "serde", feature = "csv"))]
+/// The extension describing a file's contents, skipping a compression extension (e.g. `json` for `data.json.gz`)
+pub(crate) fn content_extension(path: &std::path::Path) -> Option<&str> {
+    #[cfg(feature = "compression")]
+    let path = match Compression::from_extension(path) {
+        Some(_) => std::path::Path::new(path.file_stem()?),
+        None => path,
+    };
+    path.extension()?.to_str()
+}
+
+pub(crate) fn overwrite_refused(path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        StdinError::OverwriteRefused(path.to_owned()),
+    )
+}
+
 impl Dest {
-    pub(crate) fn into_writer(self) -> std::io::Result<impl std::io::Write> {
+    /// Parse a file arg, which refers to `stdout` when it is a sentinel
+    pub(crate) fn parse(s: &str, is_sentinel: bool) -> Self {
+        if is_sentinel {
+            return Self::Stdout;
+        }
+        match parse_fd(s) {
+            Some(1) => Self::Stdout,
+            Some(fd) => Self::Fd {
+                fd,
+                arg: s.to_owned(),
+            },
+            None => Self::Arg(s.to_owned()),
+        }
+    }
+
+    pub(crate) fn into_writer(self, mode: WriteMode) -> std::io::Result<Box<dyn std::io::Write>> {
         let input: Box<dyn std::io::Write + 'static> = match self {
-            Dest::Stdout => Box::new(std::io::stdout()),
+            Dest::Stdout => StdinContext::current().stdout(),
+            Dest::Fd { fd, .. } => Box::new(open_fd(fd)?),
             Dest::Arg(filepath) => {
                 let f = std::fs::OpenOptions::new()
                     .create(true)
                     .write(true)
-                    .truncate(true)
-                    .open(filepath)?;
+                    .truncate(mode == WriteMode::Truncate)
+                    .append(mode == WriteMode::Append)
+                    .create_new(mode == WriteMode::CreateNew)
+                    .open(&filepath)
+                    .map_err(|e| mode.open_error(e, &filepath))?;
                 Box::new(f)
             }
         };
         Ok(input)
     }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn into_async_writer(
+        self,
+        mode: WriteMode,
+    ) -> std::io::Result<std::pin::Pin<Box<dyn tokio::io::AsyncWrite>>> {
+        match self {
+            Dest::Stdout => Ok(StdinContext::current().async_stdout()),
+            Dest::Fd { fd, .. } => Ok(Box::pin(tokio::fs::File::from_std(open_fd(fd)?))),
+            Dest::Arg(filepath) => {
+                let f = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .truncate(mode == WriteMode::Truncate)
+                    .append(mode == WriteMode::Append)
+                    .create_new(mode == WriteMode::CreateNew)
+                    .open(&filepath)
+                    .await
+                    .map_err(|e| mode.open_error(e, &filepath))?;
+                Ok(Box::pin(f))
+            }
+        }
+    }
 }
 
 impl FromStr for Dest {
     type Err = std::io::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "-" => Ok(Self::Stdout),
-            arg => Ok(Self::Arg(arg.to_owned())),
-        }
+        Ok(Self::parse(
+            s,
+            StdinContext::current().is_stdout_sentinel(s),
+        ))
     }
 }
 
@@ -124,7 +412,83 @@ impl std::fmt::Debug for Dest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Dest::Stdout => write!(f, "stdout"),
-            Dest::Arg(path) => path.fmt(f),
+            Dest::Fd { arg, .. } | Dest::Arg(arg) => arg.fmt(f),
         }
     }
 }
+
+#[test]
+fn test_prompt_line() {
+    let mut output = Vec::new();
+    let line = prompt_line("Token: ", "secret\r\nignored\n".as_bytes(), &mut output).unwrap();
+    assert_eq!(line, "secret");
+    assert_eq!(output, b"Token: ");
+}
+
+#[test]
+fn test_sentinels() {
+    let context = StdinContext::new();
+    let _guard = context.enter();
+    assert!(matches!("-".parse(), Ok(Source::Stdin)));
+    assert!(matches!("./-".parse(), Ok(Source::Arg(path)) if path == "./-"));
+    assert!(matches!("/dev/stdin".parse(), Ok(Source::Arg(_))));
+
+    context.set_stdin_sentinels(["-", "/dev/stdin", "stdin"]);
+    context.set_stdout_sentinels(["/dev/stdout"]);
+    assert!(matches!("/dev/stdin".parse(), Ok(Source::Stdin)));
+    assert!(matches!("stdin".parse(), Ok(Source::Stdin)));
+    assert!(matches!("./stdin".parse(), Ok(Source::Arg(_))));
+    assert!(matches!("/dev/stdout".parse(), Ok(Dest::Stdout)));
+    assert!(matches!("-".parse(), Ok(Dest::Arg(path)) if path == "-"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fd() {
+    use std::io::{Seek, Write};
+    use std::os::fd::AsRawFd;
+
+    let context = StdinContext::new();
+    let _guard = context.enter();
+    assert!(matches!("fd:0".parse(), Ok(Source::Stdin)));
+    assert!(matches!("/dev/fd/1".parse(), Ok(Dest::Stdout)));
+    assert!(matches!("fd:x".parse(), Ok(Source::Arg(_))));
+    assert!(matches!("./fd:3".parse(), Ok(Source::Arg(_))));
+
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(b"inherited").unwrap();
+    // the duplicated descriptor shares the offset
+    file.rewind().unwrap();
+    let fd = file.as_raw_fd();
+    let mut contents = String::new();
+    let source: Source = format!("fd:{fd}").parse().unwrap();
+    assert!(matches!(source, Source::Fd { fd: n, .. } if n as i32 == fd));
+    source
+        .into_reader()
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!(contents, "inherited");
+
+    let source: Source = format!("/dev/fd/{fd}").parse().unwrap();
+    let err = source.clone().into_reader().err().unwrap();
+    assert!(matches!(err, StdinError::FdRepeatedUse(n) if n as i32 == fd));
+    let dest: Dest = format!("fd:{fd}").parse().unwrap();
+    assert!(dest.into_writer(WriteMode::Truncate).is_err());
+
+    context.reset();
+    assert!(source.into_reader().is_ok());
+
+    // A descriptor that isn't open fails without being claimed
+    let source: Source = "fd:1000000".parse().unwrap();
+    for _ in 0..2 {
+        let err = source.clone().into_reader().err().unwrap();
+        assert!(matches!(err, StdinError::StdIn(e) if e.raw_os_error() == Some(libc::EBADF)));
+    }
+
+    assert!(matches!(Source::from_value("fd:0", false), Source::Arg(_)));
+    assert!(matches!(
+        Source::from_value(&format!("fd:{fd}"), false),
+        Source::Arg(_)
+    ));
+}
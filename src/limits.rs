@@ -0,0 +1,141 @@
+use std::io::{self, Read};
+
+use super::StdinError;
+
+/// Limits on how much input is read, to protect against runaway producers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Limits {
+    pub(crate) max_size: Option<u64>,
+    pub(crate) max_line_length: Option<usize>,
+}
+
+/// Reader that fails once the input exceeds its [`Limits`]
+///
+/// The bytes before the limit was exceeded are returned first, with the error reported by the
+/// following read. After the error, EOF is reported so iterators over the input stop rather
+/// than repeating the error.
+pub(crate) struct Limited<R> {
+    inner: R,
+    limits: Limits,
+    size: u64,
+    line: usize,
+    line_length: usize,
+    pending: Option<StdinError>,
+    exceeded: bool,
+}
+
+impl<R> Limited<R> {
+    pub(crate) fn new(inner: R, limits: Limits) -> Self {
+        Self {
+            inner,
+            limits,
+            size: 0,
+            line: 1,
+            line_length: 0,
+            pending: None,
+            exceeded: false,
+        }
+    }
+
+    /// Take the pending error, or `None` when EOF should be reported
+    fn error(&mut self) -> Option<io::Error> {
+        self.exceeded = true;
+        let e = self.pending.take()?;
+        Some(io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Check the newly read data, returning how many of its bytes are within the limits
+    fn check(&mut self, data: &[u8]) -> usize {
+        let mut allowed = data.len();
+        if let Some(limit) = self.limits.max_size {
+            let remaining = limit - self.size;
+            if data.len() as u64 > remaining {
+                allowed = remaining as usize;
+                self.pending = Some(StdinError::SizeLimit { limit });
+            }
+        }
+        if let Some(limit) = self.limits.max_line_length {
+            for (i, &b) in data[..allowed].iter().enumerate() {
+                if b == b'\n' {
+                    self.line += 1;
+                    self.line_length = 0;
+                } else if self.line_length == limit {
+                    allowed = i;
+                    self.pending = Some(StdinError::LineTooLong {
+                        line: self.line,
+                        limit,
+                    });
+                    break;
+                } else {
+                    self.line_length += 1;
+                }
+            }
+        }
+        self.size += allowed as u64;
+        allowed
+    }
+}
+
+impl<R: Read> Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_some() || self.exceeded {
+            return self.error().map_or(Ok(0), Err);
+        }
+        let n = self.inner.read(buf)?;
+        match self.check(&buf[..n]) {
+            0 if n > 0 => self.error().map_or(Ok(0), Err),
+            allowed => Ok(allowed),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for Limited<R> {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        use std::task::Poll;
+
+        if self.pending.is_some() || self.exceeded {
+            return Poll::Ready(self.error().map_or(Ok(()), Err));
+        }
+        let filled = buf.filled().len();
+        let this = &mut *self;
+        std::task::ready!(std::pin::Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        let n = buf.filled().len() - filled;
+        let allowed = this.check(&buf.filled()[filled..]);
+        buf.set_filled(filled + allowed);
+        if allowed == 0 && n > 0 {
+            return Poll::Ready(this.error().map_or(Ok(()), Err));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn test_limited() {
+    let limits = Limits {
+        max_size: Some(8),
+        ..Default::default()
+    };
+    let mut output = String::new();
+    Limited::new("12345678".as_bytes(), limits)
+        .read_to_string(&mut output)
+        .unwrap();
+    assert_eq!(output, "12345678");
+
+    let mut reader = Limited::new("123456789".as_bytes(), limits);
+    let err = StdinError::from(reader.read_to_string(&mut output).unwrap_err());
+    assert!(matches!(err, StdinError::SizeLimit { limit: 8 }));
+    assert_eq!(reader.read(&mut [0; 8]).unwrap(), 0);
+
+    let limits = Limits {
+        max_line_length: Some(3),
+        ..Default::default()
+    };
+    let mut reader = Limited::new("abc\ndef\nghij\n".as_bytes(), limits);
+    let err = StdinError::from(reader.read_to_end(&mut Vec::new()).unwrap_err());
+    assert!(matches!(err, StdinError::LineTooLong { line: 3, limit: 3 }));
+}
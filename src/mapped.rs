@@ -0,0 +1,68 @@
+use std::ops::Deref;
+
+use super::StdinError;
+
+/// Contents returned by [`FileOrStdin::map`](crate::FileOrStdin::map)
+///
+/// Regular files are memory mapped, so their contents aren't copied onto the heap. Inputs that
+/// can't be mapped (`stdin`, pipes, empty or compressed files) are read into a buffer instead.
+/// Either way the contents deref to a `&[u8]`, or can be viewed as a `&str` with [`Mapped::as_str`].
+#[derive(Debug)]
+pub struct Mapped(Inner);
+
+#[derive(Debug)]
+enum Inner {
+    Map(memmap2::Mmap),
+    Buffer(Vec<u8>),
+}
+
+impl Mapped {
+    pub(crate) fn map(map: memmap2::Mmap) -> Self {
+        Self(Inner::Map(map))
+    }
+
+    pub(crate) fn buffer(buffer: Vec<u8>) -> Self {
+        Self(Inner::Buffer(buffer))
+    }
+
+    /// Are the contents memory mapped (rather than read into a buffer)
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.0, Inner::Map(_))
+    }
+
+    /// The contents as bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.0 {
+            Inner::Map(map) => map,
+            Inner::Buffer(buffer) => buffer,
+        }
+    }
+
+    /// The contents as a string, failing if they aren't valid UTF-8
+    pub fn as_str(&self) -> Result<&str, StdinError> {
+        std::str::from_utf8(self.as_bytes())
+            .map_err(|e| StdinError::StdIn(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+    }
+}
+
+impl Deref for Mapped {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for Mapped {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[test]
+fn test_mapped() {
+    let mapped = Mapped::buffer(b"buffered".to_vec());
+    assert!(!mapped.is_mapped());
+    assert_eq!(mapped.as_str().unwrap(), "buffered");
+    assert!(Mapped::buffer(vec![0xff]).as_str().is_err());
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use super::{Source, StdinError};
+use super::{Source, StdinContext, StdinError};
 
 /// Wrapper struct to parse arg values from `stdin`
 ///
@@ -45,9 +45,73 @@ where
     type Err = StdinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let source = Source::from_str(s)?;
+        let is_sentinel = StdinContext::current().is_stdin_sentinel(s);
+        Self::from_source(Source::from_value(s, is_sentinel))
+    }
+}
+
+impl<T> MaybeStdin<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    /// Parse the arg, reading from `stdin` only for the given sentinels instead of those of the
+    /// [`StdinContext`]
+    ///
+    /// Sentinels set with [`set_stdin_sentinels`](crate::set_stdin_sentinels) apply to every arg,
+    /// including `MaybeStdin` values (e.g. `stdin` would no longer be a literal value). This can be
+    /// used as a value parser to keep the sentinels of a single arg:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::MaybeStdin;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = |s: &str| MaybeStdin::<String>::parse_with_sentinels(s, &["-"]))]
+    ///     name: MaybeStdin<String>,
+    /// }
+    ///
+    /// clap_stdin::set_stdin_sentinels(["-", "stdin"]);
+    /// let args = Args::try_parse_from(["example", "stdin"]).unwrap();
+    /// assert_eq!(*args.name, "stdin");
+    /// ```
+    pub fn parse_with_sentinels(s: &str, sentinels: &[&str]) -> Result<Self, StdinError> {
+        Self::from_source(Source::from_value(s, sentinels.contains(&s)))
+    }
+
+    /// Parse the arg, prompting with the given text when reading from `stdin` on a terminal
+    ///
+    /// [`TerminalPolicy::Prompt`](crate::TerminalPolicy::Prompt) shows the same prompt for every
+    /// arg. This can be used as a value parser to give a single arg its own prompt, whatever the
+    /// terminal policy:
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::MaybeStdin;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(long, value_parser = |s: &str| MaybeStdin::<String>::parse_with_prompt(s, "Token: "))]
+    ///     token: MaybeStdin<String>,
+    ///     #[arg(long, value_parser = |s: &str| MaybeStdin::<String>::parse_with_prompt(s, "Message: "))]
+    ///     message: MaybeStdin<String>,
+    /// }
+    ///
+    /// let args = Args::parse();
+    /// ```
+    pub fn parse_with_prompt(s: &str, prompt: &str) -> Result<Self, StdinError> {
+        let is_sentinel = StdinContext::current().is_stdin_sentinel(s);
+        let source = Source::from_value(s, is_sentinel);
         let is_stdin = matches!(source, Source::Stdin);
-        T::from_str(source.get_value()?.trim())
+        Self::from_value(source.get_value_with_prompt(Some(prompt))?, is_stdin)
+    }
+
+    fn from_source(source: Source) -> Result<Self, StdinError> {
+        let is_stdin = matches!(source, Source::Stdin);
+        Self::from_value(source.get_value()?, is_stdin)
+    }
+
+    fn from_value(value: String, is_stdin: bool) -> Result<Self, StdinError> {
+        T::from_str(value.trim())
             .map_err(|e| StdinError::FromStr(format!("{e}")))
             .map(|val| Self {
                 inner: val,
@@ -94,3 +158,19 @@ impl<T> std::ops::DerefMut for MaybeStdin<T> {
         &mut self.inner
     }
 }
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_parse_with_prompt() {
+    let context = StdinContext::new();
+    context.set_terminal(true);
+    context.set_stdin("secret\nignored\n");
+    let _guard = context.enter();
+
+    let val = MaybeStdin::<String>::parse_with_prompt("-", "Token: ").unwrap();
+    assert_eq!(*val, "secret");
+    assert!(val.is_stdin());
+    let val = MaybeStdin::<String>::parse_with_prompt("literal", "Token: ").unwrap();
+    assert_eq!(*val, "literal");
+    assert!(!val.is_stdin());
+}
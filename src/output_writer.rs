@@ -0,0 +1,72 @@
+use std::io::{self, Write};
+
+#[cfg(feature = "compression")]
+type Inner = super::compression::Encoder<Box<dyn Write>>;
+#[cfg(not(feature = "compression"))]
+type Inner = Box<dyn Write>;
+
+/// Writer returned by [`FileOrStdout::into_writer`](crate::FileOrStdout::into_writer)
+///
+/// Call [`OutputWriter::finish`] once all output is written, to flush it (and finalize any
+/// compressed frame) and handle any error. If the writer is dropped without finishing, this
+/// still happens but errors are ignored.
+pub struct OutputWriter {
+    writer: Inner,
+}
+
+impl OutputWriter {
+    pub(crate) fn new(
+        writer: Box<dyn Write>,
+        #[cfg(feature = "compression")] codec: Option<super::Compression>,
+    ) -> io::Result<Self> {
+        #[cfg(feature = "compression")]
+        let writer = super::compression::Encoder::new(writer, codec)?;
+        Ok(Self { writer })
+    }
+
+    /// Finish writing, flushing the output and finalizing any compressed frame
+    pub fn finish(self) -> io::Result<()> {
+        #[cfg(feature = "compression")]
+        self.writer.finish()?;
+        #[cfg(not(feature = "compression"))]
+        {
+            let mut writer = self.writer;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl std::fmt::Debug for OutputWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutputWriter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_output_writer_finish() {
+    use std::io::Read;
+
+    let tmp = tempfile::NamedTempFile::with_suffix(".gz").unwrap();
+    let output: crate::FileOrStdout = tmp.path().to_str().unwrap().parse().unwrap();
+    let mut writer = output.into_writer().unwrap();
+    writer.write_all(b"compressed").unwrap();
+    writer.finish().unwrap();
+
+    let mut contents = String::new();
+    flate2::read::GzDecoder::new(std::fs::File::open(tmp.path()).unwrap())
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!(contents, "compressed");
+}
@@ -0,0 +1,127 @@
+use serde::de::DeserializeOwned;
+
+use super::StdinError;
+
+/// Options for reading and writing CSV/TSV records with the `csv` feature
+///
+/// By default, rows are separated by commas (or tabs for `.tsv` files) and the first row is a header.
+///
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{CsvOptions, FileOrStdin};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Row {
+///     city: String,
+///     population: u64,
+/// }
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: FileOrStdin,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let options = CsvOptions::default().with_delimiter(b'|').with_headers(false);
+/// for row in args.input.csv_records_with::<Row>(options)? {
+///     println!("{:?}", row?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    delimiter: Option<u8>,
+    has_headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            has_headers: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// The field delimiter (default: `\t` for `.tsv` files, and `,` otherwise)
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Whether the first row is a header (default: `true`)
+    ///
+    /// When reading, headers are used to match columns to struct field names. When writing,
+    /// a header row is written from the field names of the first serialized struct.
+    pub fn with_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    fn delimiter(&self, filename: &str) -> u8 {
+        self.delimiter
+            .unwrap_or(match super::content_extension(filename.as_ref()) {
+                Some("tsv") => b'\t',
+                _ => b',',
+            })
+    }
+
+    pub(crate) fn reader<R: std::io::Read>(&self, filename: &str, reader: R) -> csv::Reader<R> {
+        csv::ReaderBuilder::new()
+            .delimiter(self.delimiter(filename))
+            .has_headers(self.has_headers)
+            .from_reader(reader)
+    }
+
+    pub(crate) fn writer<W: std::io::Write>(&self, filename: &str, writer: W) -> csv::Writer<W> {
+        csv::WriterBuilder::new()
+            .delimiter(self.delimiter(filename))
+            .has_headers(self.has_headers)
+            .from_writer(writer)
+    }
+}
+
+/// Deserialize each row of the reader, mapping errors to [`StdinError`]
+pub(crate) fn records<R, U>(reader: csv::Reader<R>) -> impl Iterator<Item = Result<U, StdinError>>
+where
+    R: std::io::Read,
+    U: DeserializeOwned,
+{
+    reader.into_deserialize().map(|row| row.map_err(csv_error))
+}
+
+fn csv_error(e: csv::Error) -> StdinError {
+    if !e.is_io_error() {
+        return StdinError::Deserialize(format!("{e}"));
+    }
+    // Keep errors from reading (e.g. a size limit) as they were raised
+    match e.into_kind() {
+        csv::ErrorKind::Io(e) => StdinError::from(e),
+        _ => unreachable!("checked for an io error"),
+    }
+}
+
+#[test]
+fn test_csv_options() {
+    let options = CsvOptions::default();
+    assert_eq!(options.delimiter("rows.csv"), b',');
+    assert_eq!(options.delimiter("rows.tsv"), b'\t');
+    assert_eq!(options.delimiter("-"), b',');
+    assert_eq!(options.with_delimiter(b';').delimiter("rows.tsv"), b';');
+
+    let input = "city\tpopulation\nParis\t2102650\nLyon\tmany\n";
+    let rows: Vec<Result<(String, u64), _>> =
+        records(options.reader("rows.tsv", input.as_bytes())).collect();
+    assert_eq!(rows[0].as_ref().unwrap(), &("Paris".to_owned(), 2102650));
+    assert!(matches!(rows[1], Err(StdinError::Deserialize(_))));
+
+    let mut output = Vec::new();
+    let mut writer = options.with_headers(false).writer("-", &mut output);
+    writer.serialize(("Paris", 2102650)).unwrap();
+    drop(writer);
+    assert_eq!(output, b"Paris,2102650\n");
+}
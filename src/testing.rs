@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// In-memory replacements for the standard streams, installed in a [`StdinContext`](crate::StdinContext)
+#[derive(Debug, Default)]
+pub(crate) struct FakeStreams {
+    pub(crate) stdin: Option<Arc<[u8]>>,
+    pub(crate) terminal: Option<bool>,
+    pub(crate) stdout: Option<Capture>,
+}
+
+impl FakeStreams {
+    /// Is the fake `stdin` a terminal, or `None` to check the real `stdin`
+    ///
+    /// Installed stdin contents are treated as piped data unless a terminal is faked.
+    pub(crate) fn stdin_is_terminal(&self) -> Option<bool> {
+        self.terminal.or(self.stdin.as_ref().map(|_| false))
+    }
+}
+
+/// Writer appending to a shared buffer, standing in for `stdout`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Capture {
+    pub(crate) fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncWrite for Capture {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        std::task::Poll::Ready(self.write(buf))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn test_in_process() {
+    use clap::Parser;
+
+    use crate::{FileOrStdin, FileOrStdout, MaybeStdin, StdinContext, StdinError};
+
+    #[derive(Debug, Parser)]
+    struct Args {
+        value: MaybeStdin<String>,
+        #[arg(default_value = "-")]
+        output: FileOrStdout,
+    }
+
+    let context = StdinContext::new();
+    context.set_stdin("piped");
+    context.capture_stdout();
+    let _guard = context.enter();
+
+    let args = Args::try_parse_from(["test", "-"]).unwrap();
+    assert_eq!(*args.value, "piped");
+    let mut writer = args.output.into_writer().unwrap();
+    writer.write_all(b"written").unwrap();
+    drop(writer);
+    assert_eq!(context.take_stdout(), b"written");
+
+    let err = Args::try_parse_from(["test", "-"]).unwrap_err();
+    assert!(err.to_string().contains("stdin read from more than once"));
+
+    context.reset();
+    context.set_stdin("again");
+    let input: FileOrStdin = "-".parse().unwrap();
+    assert_eq!(input.contents().unwrap(), "again");
+
+    context.reset();
+    context.set_terminal(true);
+    let input: FileOrStdin = "-".parse().unwrap();
+    assert!(matches!(input.contents(), Err(StdinError::StdinIsTerminal)));
+}
@@ -0,0 +1,166 @@
+use std::io::Read;
+use std::str::FromStr;
+
+use super::{Source, StdinError};
+
+/// Wrapper struct to parse arg values given literally, from a file, or from `stdin`
+///
+/// Values are parsed curl-style: `@path` reads the value from a file, `@-` reads it from `stdin`,
+/// and anything else is taken literally. A literal value starting with `@` can be escaped as `@@`.
+/// As with [`MaybeStdin`](crate::MaybeStdin), the value is read while parsing the args, and
+/// `stdin` can only be read once.
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::ValueOrFile;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     body: ValueOrFile<String>,
+/// }
+///
+/// if let Ok(args) = Args::try_parse() {
+///     println!("body={}", args.body);
+/// }
+/// ```
+///
+/// ```sh
+/// $ ./example --body '{"id": 1}'
+/// body={"id": 1}
+/// $ ./example --body @body.json
+/// body={"id": 2}
+/// $ echo '{"id": 3}' | ./example --body @-
+/// body={"id": 3}
+/// $ ./example --body @@user
+/// body=@user
+/// ```
+#[derive(Clone)]
+pub struct ValueOrFile<T> {
+    inner: T,
+    source: Option<Source>,
+}
+
+impl<T> ValueOrFile<T> {
+    /// Was this value read from stdin
+    pub fn is_stdin(&self) -> bool {
+        matches!(self.source, Some(Source::Stdin))
+    }
+
+    /// The path of the file this value was read from, if any
+    pub fn path(&self) -> Option<&str> {
+        match &self.source {
+            Some(Source::Fd { arg: path, .. } | Source::Arg(path)) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Extract the inner value from the wrapper
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> FromStr for ValueOrFile<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, source) = match s.strip_prefix('@') {
+            Some(literal) if literal.starts_with('@') => (literal.to_owned(), None),
+            Some(path) => {
+                let source = Source::from_str(path)?;
+                let mut value = match &source {
+                    Source::Stdin => source.clone().get_value()?,
+                    Source::Fd { .. } | Source::Arg(_) => {
+                        let mut value = String::new();
+                        source.clone().into_reader()?.read_to_string(&mut value)?;
+                        value
+                    }
+                };
+                value.truncate(value.trim_end().len());
+                (value, Some(source))
+            }
+            None => (s.to_owned(), None),
+        };
+        T::from_str(&value)
+            .map_err(|e| StdinError::FromStr(format!("{e}")))
+            .map(|inner| Self { inner, source })
+    }
+}
+
+impl<T> std::fmt::Display for ValueOrFile<T>
+where
+    T: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T> std::fmt::Debug for ValueOrFile<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T> std::ops::Deref for ValueOrFile<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for ValueOrFile<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[test]
+fn test_value_or_file() {
+    use std::io::Write;
+
+    let val: ValueOrFile<String> = "literal".parse().unwrap();
+    assert_eq!(*val, "literal");
+    assert!(!val.is_stdin());
+    assert_eq!(val.path(), None);
+
+    let val: ValueOrFile<String> = "@@user".parse().unwrap();
+    assert_eq!(*val, "@user");
+    assert_eq!(val.path(), None);
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "42").unwrap();
+    let path = file.path().to_str().unwrap();
+    let val: ValueOrFile<u32> = format!("@{path}").parse().unwrap();
+    assert_eq!(*val, 42);
+    assert_eq!(val.path(), Some(path));
+
+    assert!("@/does/not/exist".parse::<ValueOrFile<String>>().is_err());
+    assert!(matches!(
+        format!("@{path}").parse::<ValueOrFile<bool>>(),
+        Err(StdinError::FromStr(_))
+    ));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_value_or_file_stdin() {
+    let context = crate::StdinContext::new();
+    context.set_stdin("from stdin\n");
+    let _guard = context.enter();
+    let val: ValueOrFile<String> = "@-".parse().unwrap();
+    assert_eq!(*val, "from stdin");
+    assert!(val.is_stdin());
+    assert!(matches!(
+        "@-".parse::<ValueOrFile<String>>(),
+        Err(StdinError::StdInRepeatedUse)
+    ));
+}
@@ -0,0 +1,22 @@
+use clap::{CommandFactory, FromArgMatches, Parser};
+
+use clap_stdin::Fallback;
+
+#[derive(Debug, Parser)]
+struct Args {
+    /// Token for the API
+    #[arg(long)]
+    token: Option<String>,
+}
+
+fn main() -> Result<(), String> {
+    let fallback = Fallback::new()
+        .with_env("FALLBACK_TOKEN")
+        .with_stdin()
+        .with_default("anonymous");
+    let command = Args::command().mut_arg("token", |arg| fallback.describe(arg));
+    let args = Args::from_arg_matches(&command.get_matches()).map_err(|e| format!("{e}"))?;
+    let token = fallback.resolve(args.token).map_err(|e| format!("{e}"))?;
+    println!("{token:?} {:?}", token.origin());
+    Ok(())
+}
@@ -9,7 +9,7 @@ struct Args {
     second: Option<FileOrStdin<u32>>,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() {
     let args = Args::parse();
     println!(
@@ -23,10 +23,10 @@ fn main() {
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    println!(
-        "FIRST: {}, SECOND: {:?}",
-        args.first,
-        args.second
-            .map(|second| second.contents_async().await.unwrap()),
-    );
+    let second = match args.second {
+        Some(second) => Some(second.contents_async().await?),
+        None => None,
+    };
+    println!("FIRST: {}, SECOND: {:?}", args.first, second);
+    Ok(())
 }
@@ -10,7 +10,7 @@ struct Args {
     second: Option<String>,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() -> Result<(), String> {
     let args = Args::parse();
     println!(
@@ -30,4 +30,5 @@ async fn main() -> anyhow::Result<()> {
         args.first.contents_async().await?,
         args.second
     );
+    Ok(())
 }
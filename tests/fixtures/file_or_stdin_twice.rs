@@ -8,7 +8,7 @@ struct Args {
     second: MaybeStdin<u32>,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() -> Result<(), String> {
     let args = Args::parse();
     println!(
@@ -25,8 +25,9 @@ fn main() -> Result<(), String> {
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     println!(
-        "FIRST: {}; SECOND: {}",
-        args.first.contents_async()?,
+        "FIRST: {}; SECOND: {:?}",
+        args.first.contents_async().await?,
         args.second
     );
+    Ok(())
 }
@@ -0,0 +1,35 @@
+#[cfg(not(feature = "test_bin_tokio"))]
+use std::io::Write;
+
+use clap::Parser;
+#[cfg(feature = "test_bin_tokio")]
+use tokio::io::AsyncWriteExt;
+
+use clap_stdin::FileOrStdoutAppend;
+
+#[derive(Debug, Parser)]
+struct Args {
+    #[arg(short)]
+    value: String,
+    #[arg(default_value = "-")]
+    output: FileOrStdoutAppend,
+}
+
+#[cfg(not(feature = "test_bin_tokio"))]
+fn main() {
+    let args = Args::parse();
+    let mut writer = args.output.into_writer().unwrap();
+    let _ = writeln!(&mut writer, "{}", args.value);
+}
+
+#[cfg(feature = "test_bin_tokio")]
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let mut writer = args.output.into_async_writer().await?;
+    writer
+        .write_all(format!("{}\n", args.value).as_bytes())
+        .await?;
+    writer.shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+#[cfg(not(feature = "test_bin_tokio"))]
+use std::io::Write;
+
+use clap::Parser;
+#[cfg(feature = "test_bin_tokio")]
+use tokio::io::AsyncWriteExt;
+
+use clap_stdin::FileOrStdout;
+
+#[derive(Debug, Parser)]
+struct Args {
+    #[arg(short)]
+    value: String,
+    #[arg(default_value = "-")]
+    output: FileOrStdout,
+    /// Exit with an error before committing the output
+    #[arg(long)]
+    fail: bool,
+}
+
+#[cfg(not(feature = "test_bin_tokio"))]
+fn main() -> Result<(), String> {
+    let args = Args::parse();
+    let mut writer = args
+        .output
+        .into_atomic_writer()
+        .map_err(|e| format!("{e}"))?;
+    writeln!(&mut writer, "{}", args.value).map_err(|e| format!("{e}"))?;
+    if args.fail {
+        return Err("failed before commit".to_owned());
+    }
+    writer.commit().map_err(|e| format!("{e}"))
+}
+
+#[cfg(feature = "test_bin_tokio")]
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let mut writer = args.output.into_async_atomic_writer().await?;
+    writer
+        .write_all(format!("{}\n", args.value).as_bytes())
+        .await?;
+    if args.fail {
+        anyhow::bail!("failed before commit");
+    }
+    writer.commit().await?;
+    Ok(())
+}
@@ -1,7 +1,9 @@
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 use std::io::Write;
 
 use clap::Parser;
+#[cfg(feature = "test_bin_tokio")]
+use tokio::io::AsyncWriteExt;
 
 use clap_stdin::FileOrStdout;
 
@@ -13,7 +15,7 @@ struct Args {
     output: FileOrStdout,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() {
     let args = Args::parse();
     let mut writer = args.output.into_writer().unwrap();
@@ -25,6 +27,9 @@ fn main() {
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     let mut writer = args.output.into_async_writer().await?;
-    tokio::io::AsyncWriteExt::write_all(&mut writer, &args.value.as_bytes()).await?;
+    writer
+        .write_all(format!("{}\n", args.value).as_bytes())
+        .await?;
+    writer.shutdown().await?;
     Ok(())
 }
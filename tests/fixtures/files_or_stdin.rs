@@ -0,0 +1,19 @@
+use clap::Parser;
+
+use clap_stdin::{FileOrStdin, FilesOrStdin};
+
+#[derive(Debug, Parser)]
+struct Args {
+    inputs: Vec<FileOrStdin>,
+}
+
+fn main() -> Result<(), String> {
+    let args = Args::parse();
+    let inputs = FilesOrStdin::new(args.inputs).map_err(|e| format!("{e}"))?;
+    println!(
+        "FILES: {:?}",
+        inputs.filenames().collect::<Vec<_>>().join(",")
+    );
+    println!("{}", inputs.contents().map_err(|e| format!("{e}"))?);
+    Ok(())
+}
@@ -8,7 +8,7 @@ struct Args {
     second: MaybeStdin<u32>,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() -> Result<(), String> {
     let args = Args::parse();
     println!(
@@ -29,4 +29,5 @@ async fn main() -> anyhow::Result<()> {
         args.first.is_stdin(),
         args.second.is_stdin(),
     );
+    Ok(())
 }
@@ -0,0 +1,14 @@
+use clap::Parser;
+
+use clap_stdin::MaybeStdin;
+
+#[derive(Debug, Parser)]
+struct Args {
+    value: MaybeStdin<String>,
+}
+
+fn main() {
+    clap_stdin::set_max_stdin_size(Some(8));
+    let args = Args::parse();
+    println!("{args:?}");
+}
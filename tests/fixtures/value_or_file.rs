@@ -0,0 +1,16 @@
+use clap::Parser;
+
+use clap_stdin::ValueOrFile;
+
+#[derive(Debug, Parser)]
+struct Args {
+    #[arg(long)]
+    body: ValueOrFile<String>,
+    #[arg(long)]
+    count: ValueOrFile<u32>,
+}
+
+fn main() {
+    let args = Args::parse();
+    println!("{args:?}");
+}
@@ -1,3 +1,6 @@
+// Existing tests pass `&tmp_path` where a `&str` is expected
+#![allow(clippy::needless_borrow, clippy::needless_borrows_for_generic_args)]
+
 use std::fs;
 
 use assert_cmd::Command;
@@ -60,6 +63,99 @@ fn test_maybe_stdin_optional_arg() {
         ));
 }
 
+#[test]
+fn test_maybe_stdin_limited() {
+    Command::new(cargo_bin!("maybe_stdin_limited"))
+        .write_stdin("12345678")
+        .args(["-"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"Args { value: "12345678" }"#));
+    Command::new(cargo_bin!("maybe_stdin_limited"))
+        .write_stdin("123456789")
+        .args(["-"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::SizeLimit { limit: 8 }.to_string(),
+        ));
+}
+
+#[test]
+fn test_value_or_file() {
+    Command::new(cargo_bin!("value_or_file"))
+        .args(["--body", "@@literal", "--count", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"Args { body: "@literal", count: 2 }"#,
+        ));
+    Command::new(cargo_bin!("value_or_file"))
+        .write_stdin("from stdin\n")
+        .args(["--body", "@-", "--count", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"Args { body: "from stdin", count: 2 }"#,
+        ));
+
+    let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&tmp, "3\n").expect("couldn't write to temp file");
+    let tmp_path = format!("@{}", tmp.path().to_str().unwrap());
+    Command::new(cargo_bin!("value_or_file"))
+        .write_stdin("from stdin")
+        .args(["--body", "@-", "--count", &tmp_path])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"Args { body: "from stdin", count: 3 }"#,
+        ));
+    Command::new(cargo_bin!("value_or_file"))
+        .write_stdin("2")
+        .args(["--body", "@-", "--count", "@-"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::StdInRepeatedUse.to_string(),
+        ));
+}
+
+#[test]
+fn test_fallback() {
+    Command::new(cargo_bin!("fallback"))
+        .env("FALLBACK_TOKEN", "from env")
+        .write_stdin("from stdin")
+        .args(["--token", "from arg"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#""from arg" Arg"#));
+    Command::new(cargo_bin!("fallback"))
+        .env("FALLBACK_TOKEN", "from env")
+        .write_stdin("from stdin")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#""from env" Env"#));
+    Command::new(cargo_bin!("fallback"))
+        .env_remove("FALLBACK_TOKEN")
+        .write_stdin("from stdin\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#""from stdin" Stdin"#));
+    Command::new(cargo_bin!("fallback"))
+        .env_remove("FALLBACK_TOKEN")
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#""anonymous" Default"#));
+    Command::new(cargo_bin!("fallback"))
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Token for the API [fallback: $FALLBACK_TOKEN, piped stdin, default: anonymous]",
+        ));
+}
+
 #[test]
 fn test_maybe_stdin_twice() {
     Command::new(cargo_bin!("maybe_stdin_twice"))
@@ -89,6 +185,54 @@ fn test_maybe_stdin_twice() {
         ));
 }
 
+#[cfg(unix)]
+#[test]
+fn test_file_or_stdin_fd() {
+    let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&tmp, "FILE").expect("couldn't write to temp file");
+    let bin = cargo_bin!("file_or_stdin_positional_arg").to_str().unwrap();
+
+    Command::new("sh")
+        .args([
+            "-c",
+            &format!("{bin} fd:3 3<\"$0\""),
+            tmp.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"FIRST: FILE; SECOND: None"#));
+    Command::new("sh")
+        .args(["-c", &format!("{bin} /dev/fd/3 3<&0 </dev/null")])
+        .write_stdin("PIPE")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"FIRST: PIPE; SECOND: None"#));
+    Command::new(cargo_bin!("file_or_stdin_positional_arg"))
+        .args(["fd:9"])
+        .assert()
+        .failure();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_file_or_stdout_fd_append() {
+    let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&tmp, "EXISTING LINE\n").expect("couldn't write to temp file");
+    let bin = cargo_bin!("file_or_stdout_positional_arg")
+        .to_str()
+        .unwrap();
+
+    Command::new("sh")
+        .args([
+            "-c",
+            &format!("{bin} -v NEW fd:3 3>>\"$0\""),
+            tmp.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&tmp).unwrap(), "EXISTING LINE\nNEW\n");
+}
+
 #[test]
 fn test_file_or_stdin_positional_arg() {
     let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
@@ -208,6 +352,42 @@ fn test_is_stdin() {
         ));
 }
 
+#[test]
+fn test_files_or_stdin() {
+    let first = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&first, "FIRST\n").expect("couldn't write to temp file");
+    let first_path = first.path().to_str().unwrap();
+    let second = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&second, "SECOND\n").expect("couldn't write to temp file");
+    let second_path = second.path().to_str().unwrap();
+
+    Command::new(cargo_bin!("files_or_stdin"))
+        .args([first_path, "-", second_path])
+        .write_stdin("STDIN\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("FIRST\nSTDIN\nSECOND\n"));
+    Command::new(cargo_bin!("files_or_stdin"))
+        .write_stdin("STDIN")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("FILES: \"-\"\nSTDIN"));
+
+    Command::new(cargo_bin!("files_or_stdin"))
+        .args(["-", first_path, "-"])
+        .write_stdin("STDIN")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::StdInRepeatedUse.to_string(),
+        ));
+    Command::new(cargo_bin!("files_or_stdin"))
+        .args([first_path, "does-not-exist.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does-not-exist.txt"));
+}
+
 #[test]
 fn test_file_or_stdout_positional_args() {
     let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
@@ -257,3 +437,107 @@ fn test_file_or_stdout_optional_args() {
         .success()
         .stdout(predicate::str::starts_with(r#"FILE"#));
 }
+
+#[test]
+fn test_file_or_stdout_append() {
+    let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&tmp, "EXISTING\n").expect("couldn't write to temp file");
+    let tmp_path = tmp.path().to_str().unwrap();
+
+    for _ in 0..2 {
+        Command::new(cargo_bin!("file_or_stdout_append"))
+            .args(["-v", "FILE", tmp_path])
+            .assert()
+            .success();
+    }
+    let output = String::from_utf8_lossy(&std::fs::read(tmp_path).unwrap()).to_string();
+    assert_eq!(&output, "EXISTING\nFILE\nFILE\n");
+
+    Command::new(cargo_bin!("file_or_stdout_append"))
+        .args(["-v", "FILE"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"FILE"#));
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn test_file_or_stdout_atomic() {
+    let dir = tempfile::tempdir().expect("couldn't create temp dir");
+    let tmp = dir.path().join("output.txt");
+    fs::write(&tmp, "ORIGINAL\n").expect("couldn't write to temp file");
+    let tmp_path = tmp.to_str().unwrap();
+
+    Command::new(cargo_bin!("file_or_stdout_atomic"))
+        .args(["-v", "FILE", tmp_path, "--fail"])
+        .assert()
+        .failure();
+    let output = String::from_utf8_lossy(&std::fs::read(tmp_path).unwrap()).to_string();
+    assert_eq!(&output, "ORIGINAL\n");
+    // The uncommitted temp file is cleaned up
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    Command::new(cargo_bin!("file_or_stdout_atomic"))
+        .args(["-v", "FILE", tmp_path])
+        .assert()
+        .success();
+    let output = String::from_utf8_lossy(&std::fs::read(tmp_path).unwrap()).to_string();
+    assert_eq!(&output, "FILE\n");
+
+    Command::new(cargo_bin!("file_or_stdout_atomic"))
+        .args(["-v", "FILE"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"FILE"#));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_file_or_stdin_gzip() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"COMPRESSED").unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&tmp, &compressed).expect("couldn't write to temp file");
+    let tmp_path = tmp.path().to_str().unwrap();
+
+    Command::new(cargo_bin!("file_or_stdin_positional_arg"))
+        .args([tmp_path])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"FIRST: COMPRESSED; SECOND: None"#,
+        ));
+    Command::new(cargo_bin!("file_or_stdin_positional_arg"))
+        .write_stdin(compressed)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"FIRST: COMPRESSED; SECOND: None"#,
+        ));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_file_or_stdout_gzip() {
+    use std::io::Read;
+
+    let tmp = tempfile::Builder::new()
+        .suffix(".gz")
+        .tempfile()
+        .expect("couldn't create temp file");
+    let tmp_path = tmp.path().to_str().unwrap();
+
+    Command::new(cargo_bin!("file_or_stdout_positional_arg"))
+        .args(["-v", "FILE", tmp_path])
+        .assert()
+        .success();
+    let mut output = String::new();
+    flate2::read::GzDecoder::new(fs::File::open(tmp_path).unwrap())
+        .read_to_string(&mut output)
+        .unwrap();
+    assert_eq!(&output, "FILE\n");
+}